@@ -0,0 +1,208 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    // input ended in the middle of a header, run or code
+    Truncated,
+    // input wasn't produced by this codec
+    Malformed(String),
+    // the codec can't represent this input
+    Unsupported(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "unexpected end of input"),
+            Self::Malformed(reason) => write!(f, "malformed input: {reason}"),
+            Self::Unsupported(reason) => write!(f, "unsupported input: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub trait Codec {
+    fn name(&self) -> String;
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>, Error>;
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage {
+    pub name: String,
+    pub input: usize,
+    pub output: usize,
+}
+
+impl Stage {
+    pub fn ratio(&self) -> f64 {
+        if self.input == 0 {
+            return 1.0;
+        }
+        self.output as f64 / self.input as f64
+    }
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub stages: Vec<Stage>,
+    pub output: Vec<u8>,
+}
+
+impl Report {
+    pub fn ratio(&self) -> f64 {
+        match (self.stages.first(), self.stages.last()) {
+            (Some(first), Some(last)) if first.input != 0 => {
+                last.output as f64 / first.input as f64
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "stage\t\tinput\toutput\tratio\n- - - - - - - - - - - - - - - - - -")?;
+        for stage in self.stages.iter() {
+            writeln!(
+                f,
+                "{:<12}\t{}\t{}\t{:.2}%",
+                stage.name,
+                stage.input,
+                stage.output,
+                stage.ratio() * 100.0
+            )?;
+        }
+        writeln!(f, "total\t\t\t\t{:.2}%", self.ratio() * 100.0)
+    }
+}
+
+// runs codecs one after another on encode and in reverse order on decode,
+// e.g. rle then huffman like the stages of bzip2
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Box<dyn Codec>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stage(mut self, codec: impl Codec + 'static) -> Self {
+        self.stages.push(Box::new(codec));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    // encodes data and records the size going in and out of every stage
+    pub fn report(&self, data: &[u8]) -> Result<Report, Error> {
+        let mut stages = vec![];
+        let mut output = data.to_vec();
+
+        for codec in self.stages.iter() {
+            let encoded = codec.encode(&output)?;
+            stages.push(Stage {
+                name: codec.name(),
+                input: output.len(),
+                output: encoded.len(),
+            });
+            output = encoded;
+        }
+
+        Ok(Report { stages, output })
+    }
+}
+
+impl Codec for Pipeline {
+    fn name(&self) -> String {
+        let names: Vec<String> = self.stages.iter().map(|codec| codec.name()).collect();
+        names.join("+")
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        self.stages
+            .iter()
+            .try_fold(data.to_vec(), |data, codec| codec.encode(&data))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        self.stages
+            .iter()
+            .rev()
+            .try_fold(data.to_vec(), |data, codec| codec.decode(&data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Codec, Error, Pipeline};
+
+    // prefixes every byte with a marker, doubles the size
+    struct Tag(u8);
+
+    impl Codec for Tag {
+        fn name(&self) -> String {
+            format!("tag{}", self.0)
+        }
+
+        fn encode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+            Ok(data.iter().flat_map(|&byte| [self.0, byte]).collect())
+        }
+
+        fn decode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+            if !data.len().is_multiple_of(2) {
+                return Err(Error::Truncated);
+            }
+            data.chunks(2)
+                .map(|pair| match pair[0] == self.0 {
+                    true => Ok(pair[1]),
+                    false => Err(Error::Malformed(format!("expected tag {}", self.0))),
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn pipeline_test() {
+        let pipeline = Pipeline::new().stage(Tag(1)).stage(Tag(2));
+        assert_eq!(pipeline.name(), "tag1+tag2");
+
+        let encoded = pipeline.encode(b"ab").unwrap();
+        assert_eq!(encoded, [2, 1, 2, b'a', 2, 1, 2, b'b']);
+        assert_eq!(pipeline.decode(&encoded).unwrap(), b"ab");
+
+        assert_eq!(
+            pipeline.decode(&encoded[1..]),
+            Err(Error::Truncated),
+            "errors of a stage are passed through"
+        );
+    }
+    #[test]
+    fn report_test() {
+        let pipeline = Pipeline::new().stage(Tag(1)).stage(Tag(2));
+        let report = pipeline.report(b"abc").unwrap();
+        println!("{report}");
+
+        assert_eq!(report.output, pipeline.encode(b"abc").unwrap());
+        assert_eq!(report.stages.len(), 2);
+        assert_eq!((report.stages[0].input, report.stages[0].output), (3, 6));
+        assert_eq!((report.stages[1].input, report.stages[1].output), (6, 12));
+        assert!((report.ratio() - 4.0).abs() < f64::EPSILON);
+    }
+    #[test]
+    fn empty_pipeline_test() {
+        let pipeline = Pipeline::new();
+        assert!(pipeline.is_empty());
+        assert_eq!(pipeline.encode(b"abc").unwrap(), b"abc");
+        assert_eq!(pipeline.decode(b"abc").unwrap(), b"abc");
+    }
+}
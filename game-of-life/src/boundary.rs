@@ -0,0 +1,107 @@
+use std::{fmt::Display, str::FromStr};
+
+// What a cell at the edge sees past it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Boundary {
+    // nothing lives outside the grid
+    #[default]
+    Dead,
+    // the left edge touches the right one and the top the bottom, like a donut
+    Torus,
+    // the row or column past an edge is a copy of the one on it
+    Mirror,
+    // a torus where going over the top or bottom also flips left and right
+    Klein,
+}
+
+impl Boundary {
+    pub const ALL: [Self; 4] = [Self::Dead, Self::Torus, Self::Mirror, Self::Klein];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Dead => "dead",
+            Self::Torus => "torus",
+            Self::Mirror => "mirror",
+            Self::Klein => "klein",
+        }
+    }
+
+    // where row + dr, col + dc ends up on a height x width grid, None when it is dead
+    pub fn wrap(
+        &self,
+        row: usize,
+        col: usize,
+        dr: isize,
+        dc: isize,
+        height: usize,
+        width: usize,
+    ) -> Option<(usize, usize)> {
+        let (h, w) = (height as isize, width as isize);
+        let (r, c) = (row as isize + dr, col as isize + dc);
+        let inside = (0..h).contains(&r) && (0..w).contains(&c);
+
+        let (r, c) = match self {
+            _ if inside => (r, c),
+            Self::Dead => return None,
+            Self::Torus => (r.rem_euclid(h), c.rem_euclid(w)),
+            Self::Mirror => (r.clamp(0, h - 1), c.clamp(0, w - 1)),
+            Self::Klein => {
+                let c = c.rem_euclid(w);
+                match (0..h).contains(&r) {
+                    true => (r, c),
+                    false => (r.rem_euclid(h), w - 1 - c),
+                }
+            }
+        };
+        Some((r as usize, c as usize))
+    }
+}
+
+impl FromStr for Boundary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|boundary| boundary.name() == s.trim().to_lowercase())
+            .ok_or_else(|| format!("unknown boundary '{s}', try dead, torus, mirror or klein"))
+    }
+}
+
+impl Display for Boundary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boundary::Boundary;
+
+    #[test]
+    fn wrap_test() {
+        // up and left of the top left corner of a 4 x 5 grid
+        let corner = |boundary: Boundary| boundary.wrap(0, 0, -1, -1, 4, 5);
+        assert_eq!(corner(Boundary::Dead), None);
+        assert_eq!(corner(Boundary::Torus), Some((3, 4)));
+        assert_eq!(corner(Boundary::Mirror), Some((0, 0)));
+        assert_eq!(corner(Boundary::Klein), Some((3, 0)));
+
+        // inside is the same for all of them
+        for boundary in Boundary::ALL {
+            assert_eq!(boundary.wrap(1, 2, 1, -1, 4, 5), Some((2, 1)));
+        }
+        // only going over the top or bottom flips a Klein bottle
+        assert_eq!(Boundary::Klein.wrap(2, 4, 0, 1, 4, 5), Some((2, 0)));
+        assert_eq!(Boundary::Klein.wrap(3, 1, 1, 0, 4, 5), Some((0, 3)));
+        assert_eq!(Boundary::Mirror.wrap(3, 1, 1, 1, 4, 5), Some((3, 2)));
+    }
+    #[test]
+    fn parse_test() {
+        for boundary in Boundary::ALL {
+            assert_eq!(boundary.to_string().parse(), Ok(boundary));
+        }
+        assert_eq!("Torus".parse(), Ok(Boundary::Torus));
+        assert!("sphere".parse::<Boundary>().is_err());
+    }
+}
@@ -2,6 +2,8 @@ use std::{thread::sleep, time::Duration};
 
 use rand::{rngs::StdRng, Rng, SeedableRng};
 
+use crate::{boundary::Boundary, rule::Rule};
+
 pub const DEATH: &str = "\x1B[47m  \x1B[0m"; //WHITE
 pub const LIFE: &str = "\x1B[42m  \x1B[0m"; //GREEN
 
@@ -12,6 +14,8 @@ pub struct Conway {
     height: usize,
     width: usize,
     tick: usize,
+    rule: Rule,
+    boundary: Boundary,
     grid: Vec<bool>,
     next_grid: Vec<bool>,
 }
@@ -27,6 +31,8 @@ impl Conway {
             width,
             population,
             tick: 0,
+            rule: Rule::default(),
+            boundary: Boundary::default(),
             grid: vec![false; size],
             next_grid: vec![false; size],
         }
@@ -40,10 +46,6 @@ impl Conway {
         self.grid[row * self.width + col] = b;
     }
 
-    fn get_next(&self, row: usize, col: usize) -> Option<&bool> {
-        self.next_grid.get(row * self.width + col)
-    }
-
     fn set_next(&mut self, row: usize, col: usize, b: bool) {
         self.next_grid[row * self.width + col] = b;
     }
@@ -53,6 +55,16 @@ impl Conway {
         self.rng = StdRng::seed_from_u64(seed);
     }
 
+    // B3/S23 unless set
+    pub fn rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    // dead border unless set
+    pub fn boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+    }
+
     pub fn random_generation(&mut self) {
         let mut population = self.population;
         let mut row;
@@ -69,16 +81,16 @@ impl Conway {
 
     pub fn neighbour_count(&self, row: usize, col: usize) -> usize {
         let mut count: usize = 0;
-        for i in 0..3 {
-            for j in 0..3 {
-                if i == 1 && j == 1 {
+        for dr in -1..=1 {
+            for dc in -1..=1 {
+                if dr == 0 && dc == 0 {
                     continue;
                 }
 
-                let new_row = row.wrapping_add(i).wrapping_sub(1);
-                let new_col = col.wrapping_add(j).wrapping_sub(1);
-
-                if new_row < self.height && new_col < self.width {
+                let neighbour = self
+                    .boundary
+                    .wrap(row, col, dr, dc, self.height, self.width);
+                if let Some((new_row, new_col)) = neighbour {
                     if let Some(&true) = self.get(new_row, new_col) {
                         count += 1;
                     }
@@ -93,16 +105,13 @@ impl Conway {
         for row in 0..self.height {
             for col in 0..self.width {
                 let count = self.neighbour_count(row, col);
-                if *self.get(row, col).unwrap() {
-                    if !(2..=3).contains(&count) {
-                        self.set_next(row, col, false);
-                        self.population -= 1;
-                    } else {
-                        self.set_next(row, col, true);
-                    }
-                } else if count == 3 {
-                    self.set_next(row, col, true);
-                    self.population += 1;
+                let alive = *self.get(row, col).unwrap();
+                let next = self.rule.next(alive, count);
+                self.set_next(row, col, next);
+                match (alive, next) {
+                    (true, false) => self.population -= 1,
+                    (false, true) => self.population += 1,
+                    _ => {}
                 }
             }
         }
@@ -118,6 +127,8 @@ impl Conway {
     pub fn render(&self) {
         print!("\x1b[H");
         println!("seed: {}", self.seed);
+        println!("rule: {}", self.rule);
+        println!("boundary: {}", self.boundary);
         println!("tick: {}", self.tick);
         println!("population: {}", self.population);
         for row in 0..self.height {
@@ -144,3 +155,48 @@ impl Conway {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{boundary::Boundary, conway::Conway};
+
+    const GLIDER: [(usize, usize); 5] = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+
+    fn glider(boundary: Boundary) -> Conway {
+        let mut conway = Conway::new(0, GLIDER.len(), 6, 6);
+        conway.boundary(boundary);
+        for (row, col) in GLIDER {
+            conway.set(row, col, true);
+        }
+        conway
+    }
+
+    #[test]
+    fn neighbour_count_test() {
+        // the corner cell sees the glider's far edge only through the border
+        let count = |boundary: Boundary| glider(boundary).neighbour_count(5, 5);
+        assert_eq!(count(Boundary::Dead), 0);
+        assert_eq!(count(Boundary::Torus), 0);
+        assert_eq!(count(Boundary::Klein), 1);
+        assert_eq!(glider(Boundary::Torus).neighbour_count(5, 0), 1);
+        assert_eq!(glider(Boundary::Mirror).neighbour_count(0, 1), 2);
+    }
+    #[test]
+    fn torus_test() {
+        // a glider moves a cell down and right every 4 ticks, so it is back after 4 * 6
+        let mut torus = glider(Boundary::Torus);
+        let start = torus.grid.clone();
+        for tick in 1..=24 {
+            torus.update();
+            assert_eq!(torus.population, GLIDER.len());
+            assert_eq!(torus.grid == start, tick == 24);
+        }
+
+        // the same glider turns into a block at the dead corner
+        let mut dead = glider(Boundary::Dead);
+        for _ in 0..24 {
+            dead.update();
+        }
+        assert_eq!(dead.population, 4);
+    }
+}
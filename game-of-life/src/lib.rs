@@ -1 +1,3 @@
+pub mod boundary;
 pub mod conway;
+pub mod rule;
@@ -1,4 +1,4 @@
-use game_of_life::conway::Conway;
+use game_of_life::{boundary::Boundary, conway::Conway, rule::Rule};
 use std::env;
 
 fn main() {
@@ -9,6 +9,26 @@ fn main() {
     let height = 15;
     let width = 20;
     let mut conway = Conway::new(seed, population, height, width);
+    // cargo run -- B36/S23
+    if let Some(rule) = env::args().nth(1) {
+        match rule.parse::<Rule>() {
+            Ok(rule) => conway.rule(rule),
+            Err(error) => {
+                eprintln!("{error}");
+                return;
+            }
+        }
+    }
+    // cargo run -- B3/S23 torus
+    if let Some(boundary) = env::args().nth(2) {
+        match boundary.parse::<Boundary>() {
+            Ok(boundary) => conway.boundary(boundary),
+            Err(error) => {
+                eprintln!("{error}");
+                return;
+            }
+        }
+    }
     conway.random_generation();
     //conway.set(1, 0, true);
     //conway.set(1, 1, true);
@@ -0,0 +1,133 @@
+use std::{fmt::Display, str::FromStr};
+
+// Outer totalistic rule in B/S notation: the neighbour counts that bring a dead cell
+// to life and the ones that keep a live cell alive, as bit sets of 0..=8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+impl Rule {
+    pub const CONWAY: Rule = Rule::new(&[3], &[2, 3]);
+    pub const HIGHLIFE: Rule = Rule::new(&[3, 6], &[2, 3]);
+    pub const SEEDS: Rule = Rule::new(&[2], &[]);
+    pub const DAY_AND_NIGHT: Rule = Rule::new(&[3, 6, 7, 8], &[3, 4, 6, 7, 8]);
+    pub const LIFE_WITHOUT_DEATH: Rule = Rule::new(&[3], &[0, 1, 2, 3, 4, 5, 6, 7, 8]);
+
+    // counts above 8 are ignored, a cell has no more neighbours
+    pub const fn new(birth: &[usize], survival: &[usize]) -> Self {
+        Self {
+            birth: bits(birth),
+            survival: bits(survival),
+        }
+    }
+
+    pub fn born(&self, count: usize) -> bool {
+        count <= 8 && self.birth & (1 << count) != 0
+    }
+
+    pub fn survives(&self, count: usize) -> bool {
+        count <= 8 && self.survival & (1 << count) != 0
+    }
+
+    // whether the cell is alive in the next generation
+    pub fn next(&self, alive: bool, count: usize) -> bool {
+        match alive {
+            true => self.survives(count),
+            false => self.born(count),
+        }
+    }
+}
+
+const fn bits(counts: &[usize]) -> u16 {
+    let mut bits = 0;
+    let mut i = 0;
+    while i < counts.len() {
+        if counts[i] <= 8 {
+            bits |= 1 << counts[i];
+        }
+        i += 1;
+    }
+    bits
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::CONWAY
+    }
+}
+
+// "B36/S23", case does not matter and S may come first
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut birth = None;
+        let mut survival = None;
+
+        for part in s.trim().split('/') {
+            let mut chars = part.chars();
+            let kind = match chars.next().map(|c| c.to_ascii_uppercase()) {
+                Some('B') => &mut birth,
+                Some('S') => &mut survival,
+                _ => return Err(format!("'{part}' in rule '{s}' doesn't start with B or S")),
+            };
+            if kind.is_some() {
+                return Err(format!("rule '{s}' has '{}' twice", &part[..1]));
+            }
+            let mut bits = 0;
+            for c in chars {
+                match c.to_digit(10).filter(|count| *count <= 8) {
+                    Some(count) => bits |= 1 << count,
+                    None => return Err(format!("'{c}' in rule '{s}' isn't a count from 0 to 8")),
+                }
+            }
+            *kind = Some(bits);
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Self { birth, survival }),
+            _ => Err(format!("rule '{s}' needs a B and an S part like B3/S23")),
+        }
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let counts = |bits: u16| -> String {
+            (0..=8)
+                .filter(|count| bits & (1 << count) != 0)
+                .map(|count| count.to_string())
+                .collect()
+        };
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rule::Rule;
+
+    #[test]
+    fn parse_test() {
+        assert_eq!("B3/S23".parse(), Ok(Rule::CONWAY));
+        assert_eq!("b36/s23".parse(), Ok(Rule::HIGHLIFE));
+        assert_eq!("S/B2".parse(), Ok(Rule::SEEDS));
+        assert_eq!("B3678/S34678".parse(), Ok(Rule::DAY_AND_NIGHT));
+        assert_eq!("B3/S012345678".parse(), Ok(Rule::LIFE_WITHOUT_DEATH));
+
+        assert!("B3".parse::<Rule>().is_err());
+        assert!("B3/S29".parse::<Rule>().is_err());
+        assert!("B3/B2".parse::<Rule>().is_err());
+        assert!("3/23".parse::<Rule>().is_err());
+    }
+    #[test]
+    fn rule_test() {
+        assert_eq!(Rule::default().to_string(), "B3/S23");
+        assert_eq!(Rule::SEEDS.to_string(), "B2/S");
+        assert!(Rule::HIGHLIFE.born(6) && !Rule::CONWAY.born(6));
+        assert!(Rule::CONWAY.next(true, 2) && !Rule::CONWAY.next(false, 2));
+        assert!(!Rule::CONWAY.next(true, 4) && !Rule::CONWAY.next(true, 9));
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::{BTreeMap, BinaryHeap, HashMap};
 
+use codec::{Codec, Error};
+
 #[derive(PartialEq, Eq, Debug)]
 pub struct HuffmanNode {
     frequency: usize,
@@ -45,11 +47,15 @@ impl HuffmanNode {
     }
 
     pub fn tree(data: &str) -> Option<Box<Self>> {
+        Self::tree_from_bytes(data.as_bytes())
+    }
+
+    pub fn tree_from_bytes(data: &[u8]) -> Option<Box<Self>> {
         let mut weights = HashMap::new();
 
         let mut queue = BinaryHeap::new();
 
-        for i in data.as_bytes() {
+        for i in data {
             let freq = weights.entry(*i).or_insert(0);
             *freq += 1;
         }
@@ -92,6 +98,50 @@ impl HuffmanTable {
         println!("Counts:  {:?}", self.counts);
         println!("Symbols: {:?}", self.symbols);
     }
+
+    // [number of lengths: u8] [length: u8, count: u16 LE]... [symbols]...
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = vec![self.counts.len() as u8];
+
+        for (length, count) in self.counts.iter() {
+            result.push(*length as u8);
+            result.extend_from_slice(&(*count as u16).to_le_bytes());
+        }
+
+        result.extend_from_slice(&self.symbols);
+
+        result
+    }
+
+    // returns the table and the number of bytes it took up
+    pub fn from_bytes(data: &[u8]) -> Result<(Self, usize), Error> {
+        let lengths = *data.first().ok_or(Error::Truncated)? as usize;
+        let header = 1 + lengths * 3;
+
+        let entries = data.get(1..header).ok_or(Error::Truncated)?;
+
+        let mut counts = BTreeMap::new();
+
+        for entry in entries.chunks_exact(3) {
+            let count = u16::from_le_bytes([entry[1], entry[2]]) as usize;
+            if count == 0 || counts.insert(entry[0] as usize, count).is_some() {
+                return Err(Error::Malformed("invalid code length table".into()));
+            }
+        }
+
+        let total: usize = counts.values().sum();
+
+        if total > 256 {
+            return Err(Error::Malformed(format!("{total} symbols in table")));
+        }
+
+        let symbols = data
+            .get(header..header + total)
+            .ok_or(Error::Truncated)?
+            .to_vec();
+
+        Ok((Self { counts, symbols }, header + total))
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -153,10 +203,17 @@ impl HuffmanCode {
         let mut result = vec![];
 
         let mut bits = 0;
+        let mut length = 0;
 
         for symbol in &table.symbols {
             let mut entry = table.counts.first_entry().unwrap();
 
+            // lengths may skip, e.g. 1 and 3, so shift once per missing bit
+            while length < *entry.key() {
+                bits <<= 1;
+                length += 1;
+            }
+
             result.push(HuffmanCode {
                 value: *symbol,
                 frequency: *entry.get(),
@@ -169,7 +226,6 @@ impl HuffmanCode {
             bits += 1;
 
             if *entry.get() == 0 {
-                bits <<= 1;
                 entry.remove();
             }
         }
@@ -256,6 +312,124 @@ pub fn decode(data: &str, mut table: HuffmanTable) -> Option<Vec<u8>> {
     Some(result)
 }
 
+// byte oriented huffman: [table] [original length: u64 LE] [codes packed msb first]
+pub struct Huffman;
+
+impl Codec for Huffman {
+    fn name(&self) -> String {
+        "huffman".into()
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let tree = HuffmanNode::tree_from_bytes(data);
+
+        let codes = HuffmanCode::from_tree(&tree);
+
+        let canonical = HuffmanCode::as_canonical(&codes);
+
+        let mut lookup = [None; 256];
+
+        for code in canonical.iter() {
+            lookup[code.value as usize] = Some(code);
+        }
+
+        let mut result = HuffmanTable::from_codes(&canonical).to_bytes();
+
+        result.extend_from_slice(&(data.len() as u64).to_le_bytes());
+
+        let mut byte = 0u8;
+        let mut filled = 0;
+
+        for i in data {
+            let code = lookup[*i as usize].unwrap();
+            for shift in (0..code.length).rev() {
+                byte = byte << 1 | (code.bits >> shift & 0x1) as u8;
+                filled += 1;
+                if filled == 8 {
+                    result.push(byte);
+                    byte = 0;
+                    filled = 0;
+                }
+            }
+        }
+
+        if filled > 0 {
+            result.push(byte << (8 - filled));
+        }
+
+        Ok(result)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let (mut table, offset) = HuffmanTable::from_bytes(data)?;
+
+        let length = data.get(offset..offset + 8).ok_or(Error::Truncated)?;
+        let length = u64::from_le_bytes(length.try_into().unwrap()) as usize;
+
+        let data = &data[offset + 8..];
+
+        let codes = HuffmanCode::from_table(&mut table);
+
+        let max_length = codes.iter().map(|code| code.length).max().unwrap_or(0);
+
+        let lookup: HashMap<(usize, u128), u8> = codes
+            .iter()
+            .map(|code| ((code.length, code.bits), code.value))
+            .collect();
+
+        let mut result = Vec::new();
+
+        if max_length == 0 {
+            // a single symbol doesn't need any bits, so only the header says how many there are
+            if length > 0 {
+                let code = codes.first().ok_or(Error::Truncated)?;
+                if !data.is_empty() {
+                    return Err(Error::Malformed("bits after a single symbol".into()));
+                }
+                result
+                    .try_reserve_exact(length)
+                    .map_err(|_| Error::Malformed(format!("length {length} is too large")))?;
+                result.resize(length, code.value);
+            }
+            return Ok(result);
+        }
+
+        // every symbol takes at least a bit
+        if length > data.len().saturating_mul(8) {
+            return Err(Error::Truncated);
+        }
+        result.reserve_exact(length);
+
+        let mut bits: u128 = 0;
+        let mut width = 0;
+
+        'outer: for byte in data {
+            for shift in (0..8).rev() {
+                if result.len() == length {
+                    break 'outer;
+                }
+
+                bits = bits << 1 | (byte >> shift & 0x1) as u128;
+                width += 1;
+
+                if let Some(value) = lookup.get(&(width, bits)) {
+                    result.push(*value);
+                    bits = 0;
+                    width = 0;
+                } else if width == max_length {
+                    return Err(Error::Malformed("unknown code".into()));
+                }
+            }
+        }
+
+        if result.len() < length {
+            return Err(Error::Truncated);
+        }
+
+        Ok(result)
+    }
+}
+
 // pub fn decode(&self, bits: u128) -> Option<u8> {
 //     let mut length = 1;
 //     let mut first: u128 = 0;
@@ -287,7 +461,9 @@ pub fn decode(data: &str, mut table: HuffmanTable) -> Option<Vec<u8>> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{decode, encode};
+    use codec::{Codec, Error};
+
+    use crate::{decode, encode, Huffman, HuffmanTable};
 
     #[test]
     fn compression_test() {
@@ -313,4 +489,49 @@ mod tests {
             (len_compressed / len_uncompressed) * 100.0
         );
     }
+    #[test]
+    fn codec_test() {
+        let message = "the quick brown fox jumps over the lazy dog";
+
+        let encoded = Huffman.encode(message.as_bytes()).unwrap();
+        println!("Encoded: {} bytes", encoded.len());
+
+        assert_eq!(Huffman.decode(&encoded).unwrap(), message.as_bytes());
+        assert_eq!(
+            Huffman.decode(&encoded[..encoded.len() - 1]),
+            Err(Error::Truncated)
+        );
+    }
+    #[test]
+    fn codec_edge_cases_test() {
+        // one frequent symbol and four rare ones give code lengths 1 and 3
+        let mut skewed = vec![b'a'; 64];
+        skewed.extend_from_slice(b"bcde");
+        let binary: Vec<u8> = (0..=255).chain(0..16).collect();
+
+        for data in [vec![], vec![b'x'; 10], skewed, binary] {
+            let encoded = Huffman.encode(&data).unwrap();
+            assert_eq!(Huffman.decode(&encoded).unwrap(), data);
+        }
+    }
+    #[test]
+    fn corrupted_length_test() {
+        let corrupt = |data: &[u8], length: u64| {
+            let mut encoded = Huffman.encode(data).unwrap();
+            let (_, offset) = HuffmanTable::from_bytes(&encoded).unwrap();
+            encoded[offset..offset + 8].copy_from_slice(&length.to_le_bytes());
+            Huffman.decode(&encoded)
+        };
+
+        let message = b"the quick brown fox jumps over the lazy dog";
+        assert_eq!(corrupt(message, u64::MAX), Err(Error::Truncated));
+        assert_eq!(corrupt(message, 1000), Err(Error::Truncated));
+        assert_eq!(corrupt(message, 3).unwrap(), b"the");
+
+        assert!(matches!(
+            corrupt(&[b'x'; 10], u64::MAX),
+            Err(Error::Malformed(_))
+        ));
+        assert_eq!(corrupt(&[b'x'; 10], 4).unwrap(), b"xxxx");
+    }
 }
@@ -0,0 +1,27 @@
+// cargo bench --bench astar
+use std::time::Instant;
+
+use maze_generator::{Maze, MazeAlgorithm};
+
+fn main() {
+    for algorithm in [MazeAlgorithm::RecursiveBacktracker, MazeAlgorithm::Kruskal] {
+        for size in [250, 500, 1000] {
+            let maze = Maze::builder(size, size)
+                .algorithm(algorithm)
+                .seed(1)
+                .build()
+                .unwrap();
+
+            let now = Instant::now();
+            let result = maze.astar().unwrap();
+            let elapsed = now.elapsed();
+
+            println!(
+                "{:<12} {size}x{size}\t{elapsed:?}\tcost: {}\texpanded: {}",
+                algorithm.name(),
+                result.cost,
+                result.expanded.len()
+            );
+        }
+    }
+}
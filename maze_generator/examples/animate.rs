@@ -0,0 +1,34 @@
+// cargo run --example animate -- [DELAY_MS] [PATHFINDER]
+use std::{env, io, time::Duration};
+
+use maze_generator::{animation::Animation, pathfinding, Maze};
+
+fn main() -> io::Result<()> {
+    let mut args = env::args().skip(1);
+    let delay = args.next().and_then(|ms| ms.parse().ok()).unwrap_or(20);
+    let name = args.next().unwrap_or_else(|| "a*".into());
+
+    let pathfinder = pathfinding::ALL
+        .into_iter()
+        .find(|pathfinder| pathfinder.name() == name)
+        .unwrap_or_else(|| panic!("unknown pathfinder '{name}'"));
+
+    let (maze, generation) = Maze::builder(21, 31)
+        .braid(0.3)
+        .build_steps()
+        .map_err(io::Error::other)?;
+    let search = maze.search_steps(pathfinder);
+
+    let animation = Animation::new(Duration::from_millis(delay));
+    let mut out = io::stdout();
+    animation.generation(&mut out, &maze, &generation)?;
+    animation.search(&mut out, &maze, &search)?;
+
+    println!(
+        "seed: {}, {}: {} steps",
+        maze.seed,
+        pathfinder.name(),
+        search.len()
+    );
+    Ok(())
+}
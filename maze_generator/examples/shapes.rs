@@ -0,0 +1,56 @@
+// cargo run --example shapes -- [DIRECTORY] [SEED]
+// writes a solved maze of every topology as an SVG file
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use maze_generator::{
+    topology::{Hex, Polar, Square, Triangle},
+    GraphMaze, Topology,
+};
+
+fn write<T: Topology + Clone>(directory: &Path, topology: T, seed: u64) -> io::Result<()> {
+    let maze = GraphMaze::builder(topology)
+        .seed(seed)
+        .build()
+        .map_err(io::Error::other)?;
+    let path = maze.astar().map(|result| result.path).unwrap_or_default();
+
+    let file = directory.join(format!("{}.svg", maze.topology.name()));
+    fs::write(&file, maze.to_svg(20, &path))?;
+    println!("{}", file.display());
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    let mut args = env::args().skip(1);
+    let directory = PathBuf::from(args.next().unwrap_or_else(|| ".".into()));
+    let seed = args.next().and_then(|seed| seed.parse().ok()).unwrap_or(1);
+
+    write(
+        &directory,
+        Square {
+            height: 15,
+            width: 20,
+        },
+        seed,
+    )?;
+    write(
+        &directory,
+        Hex {
+            height: 15,
+            width: 18,
+        },
+        seed,
+    )?;
+    write(
+        &directory,
+        Triangle {
+            height: 15,
+            width: 36,
+        },
+        seed,
+    )?;
+    write(&directory, Polar::new(12), seed)
+}
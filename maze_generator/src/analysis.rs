@@ -0,0 +1,187 @@
+// Numbers that describe how hard a maze is, all counted in steps with the movement
+// of the maze and without terrain costs.
+
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use crate::{pathfinding::Bfs, Maze, Pathfinder, Point};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub cells: usize,
+    // open cells with one way out
+    pub dead_ends: usize,
+    // open cells with three or more ways out
+    pub junctions: usize,
+    // average ways out of a junction, 0 without any
+    pub branching_factor: f64,
+    // the longest of the shortest paths between two open cells
+    pub diameter: usize,
+    // average length of the corridors from a dead end up to the first junction, long
+    // ones flow like a river, short ones make a maze with many small side branches
+    pub river_factor: f64,
+    // steps from the start to the destanation, None without a path
+    pub solution_length: Option<usize>,
+    // share of the open cells on the solution, start and destanation included
+    pub solution_share: f64,
+}
+
+impl Maze {
+    // runs a breadth first search from every open cell for the diameter, so it takes
+    // a while on big mazes
+    pub fn analyze(&self) -> Analysis {
+        let open: Vec<Point> = self
+            .grid
+            .iter()
+            .flatten()
+            .filter(|cell| !cell.obsticle)
+            .map(|cell| cell.point)
+            .collect();
+        let degree = |point: &Point| self.moves(point).len();
+
+        let dead_ends: Vec<&Point> = open.iter().filter(|point| degree(point) == 1).collect();
+        let junctions: Vec<usize> = open
+            .iter()
+            .map(degree)
+            .filter(|degree| *degree >= 3)
+            .collect();
+        let branching_factor = match junctions.is_empty() {
+            true => 0.0,
+            false => junctions.iter().sum::<usize>() as f64 / junctions.len() as f64,
+        };
+
+        let diameter = open
+            .iter()
+            .map(|point| self.farthest(point).1)
+            .max()
+            .unwrap_or(0);
+
+        let corridors: usize = dead_ends.iter().map(|point| self.corridor(point)).sum();
+        let river_factor = match dead_ends.is_empty() {
+            true => 0.0,
+            false => corridors as f64 / dead_ends.len() as f64,
+        };
+
+        // the fewest steps, the cheapest path may take a detour around the terrain
+        let solution = Bfs.search(self).map(|result| result.path);
+        let solution_share = match (&solution, open.is_empty()) {
+            (Some(path), false) => path.len() as f64 / open.len() as f64,
+            _ => 0.0,
+        };
+
+        Analysis {
+            cells: open.len(),
+            dead_ends: dead_ends.len(),
+            junctions: junctions.len(),
+            branching_factor,
+            diameter,
+            river_factor,
+            solution_length: solution.map(|path| path.len() - 1),
+            solution_share,
+        }
+    }
+
+    // the open cell the most steps away from the point and the number of steps,
+    // the last one found on ties
+    pub fn farthest(&self, from: &Point) -> (Point, usize) {
+        let mut distances = HashMap::from([(*from, 0)]);
+        let mut queue = VecDeque::from([*from]);
+        let mut farthest = (*from, 0);
+        // breadth first, so the points come out in order of their distance
+        while let Some(point) = queue.pop_front() {
+            let distance = distances[&point];
+            farthest = (point, distance);
+            for (neighbor, _) in self.moves(&point) {
+                if let Entry::Vacant(entry) = distances.entry(neighbor) {
+                    entry.insert(distance + 1);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        farthest
+    }
+
+    // cells from a dead end along the corridor, up to but without the first junction
+    fn corridor(&self, dead_end: &Point) -> usize {
+        let (mut previous, mut point) = (*dead_end, *dead_end);
+        let mut length = 1;
+        loop {
+            let next: Vec<Point> = self
+                .moves(&point)
+                .into_iter()
+                .map(|(next, _)| next)
+                .filter(|next| *next != previous)
+                .collect();
+            // a junction ahead, or the other end of a maze that is a single corridor
+            if next.len() != 1 || self.moves(&next[0]).len() >= 3 {
+                return length;
+            }
+            (previous, point) = (point, next[0]);
+            length += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Maze, MazeAlgorithm, Point};
+
+    #[test]
+    fn analyze_test() {
+        let maze = Maze::from_ascii(
+            "\
+S....
+.###.
+.#...
+.#.#.
+...#E",
+        )
+        .unwrap();
+        let analysis = maze.analyze();
+
+        assert_eq!(analysis.cells, 18);
+        // E at the end of a corridor off a loop through all the other cells
+        assert_eq!(analysis.dead_ends, 1);
+        assert_eq!(analysis.junctions, 1);
+        assert_eq!(analysis.branching_factor, 3.0);
+        assert_eq!(analysis.diameter, 10);
+        assert_eq!(analysis.river_factor, 2.0);
+        assert_eq!(analysis.solution_length, Some(8));
+        assert_eq!(analysis.solution_share, 9.0 / 18.0);
+
+        // straight through the water, the cheapest path goes around it
+        let maze = Maze::from_ascii("S~~~E\n.###.\n.....").unwrap();
+        assert_eq!(maze.astar().unwrap().path.len() - 1, 8);
+        assert_eq!(maze.analyze().solution_length, Some(4));
+    }
+    #[test]
+    fn perfect_test() {
+        for algorithm in MazeAlgorithm::ALL {
+            let maze = Maze::builder(21, 31)
+                .algorithm(algorithm)
+                .seed(4)
+                .build()
+                .unwrap();
+            let analysis = maze.analyze();
+
+            // start and destanation are the two ends of the longest path
+            assert_eq!(analysis.solution_length, Some(analysis.diameter));
+            assert_eq!(maze.farthest(&maze.start).1, analysis.diameter);
+            assert!(analysis.dead_ends >= 2, "{}", algorithm.name());
+            assert!(analysis.solution_share > 0.0 && analysis.solution_share <= 1.0);
+            assert!(analysis.river_factor >= 1.0);
+        }
+    }
+    #[test]
+    fn farthest_test() {
+        let maze = Maze::from_ascii("S...E").unwrap();
+        assert_eq!(
+            maze.farthest(&Point { x: 0, y: 0 }),
+            (Point { x: 4, y: 0 }, 4)
+        );
+        assert_eq!(
+            maze.farthest(&Point { x: 2, y: 0 }),
+            (Point { x: 4, y: 0 }, 2)
+        );
+        assert_eq!(maze.analyze().river_factor, 5.0);
+    }
+}
@@ -0,0 +1,258 @@
+// Replays the steps of a generation or a search as frames in the terminal.
+// Every frame moves the cursor back to the top left and draws over the previous one.
+
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    pathfinding::SearchStep, GenerationStep, Maze, Point, CURRENT, DESTINATION, EXPANDED, FRONTIER,
+    OBSTICLE, PATH, START, TRACE,
+};
+
+const HOME: &str = "\x1B[H";
+const CLEAR: &str = "\x1B[2J";
+
+// state of a search after some of its steps
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFrame {
+    pub frontier: HashSet<Point>,
+    pub expanded: HashSet<Point>,
+    // the point expanded last
+    pub current: Option<Point>,
+    pub path: Vec<Point>,
+}
+
+impl SearchFrame {
+    pub fn apply(&mut self, step: &SearchStep) {
+        match step {
+            SearchStep::Frontier(point) => {
+                self.frontier.insert(*point);
+            }
+            SearchStep::Expand(point) => {
+                self.frontier.remove(point);
+                self.expanded.insert(*point);
+                self.current = Some(*point);
+            }
+            SearchStep::Path(path) => {
+                self.path = path.clone();
+                self.current = None;
+            }
+        }
+    }
+}
+
+// the state after every step, each one is a copy so better kept to small mazes
+pub fn search_frames(steps: &[SearchStep]) -> impl Iterator<Item = SearchFrame> + '_ {
+    steps.iter().scan(SearchFrame::default(), |frame, step| {
+        frame.apply(step);
+        Some(frame.clone())
+    })
+}
+
+// the walls after every step, starting from a grid full of walls
+pub fn generation_frames(
+    height: usize,
+    width: usize,
+    steps: &[GenerationStep],
+) -> impl Iterator<Item = Vec<Vec<bool>>> + '_ {
+    steps
+        .iter()
+        .scan(vec![vec![true; width]; height], |walls, step| {
+            walls[step.point.y][step.point.x] = step.obsticle;
+            Some(walls.clone())
+        })
+}
+
+fn render_walls(walls: &[Vec<bool>]) -> String {
+    let mut result = String::new();
+    for row in walls {
+        for &wall in row {
+            result.push_str(if wall { OBSTICLE } else { PATH });
+        }
+        result.push('\n');
+    }
+    result
+}
+
+pub(crate) fn render_search(maze: &Maze, frame: &SearchFrame) -> String {
+    let path: HashSet<&Point> = frame.path.iter().collect();
+
+    let mut result = String::new();
+    for row in maze.grid.iter() {
+        for cell in row.iter() {
+            let colour = if cell.obsticle {
+                OBSTICLE
+            } else if cell.point == maze.start {
+                START
+            } else if cell.point == maze.destanation {
+                DESTINATION
+            } else if path.contains(&cell.point) {
+                TRACE
+            } else if frame.current == Some(cell.point) {
+                CURRENT
+            } else if frame.frontier.contains(&cell.point) {
+                FRONTIER
+            } else if frame.expanded.contains(&cell.point) {
+                EXPANDED
+            } else {
+                cell.terrain.colour()
+            };
+            result.push_str(colour);
+        }
+        result.push('\n');
+    }
+    result
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    // pause after every frame
+    pub delay: Duration,
+    // steps applied between two frames, speeds up big mazes
+    pub steps_per_frame: usize,
+}
+
+impl Animation {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            steps_per_frame: 1,
+        }
+    }
+
+    fn show(&self, out: &mut impl Write, frame: &str) -> io::Result<()> {
+        write!(out, "{HOME}{frame}")?;
+        out.flush()?;
+        thread::sleep(self.delay);
+        Ok(())
+    }
+
+    fn frame_due(&self, step: usize, steps: usize) -> bool {
+        (step + 1).is_multiple_of(self.steps_per_frame.max(1)) || step + 1 == steps
+    }
+
+    // carves the maze cell by cell, ends on the finished maze with its start and destanation
+    pub fn generation(
+        &self,
+        out: &mut impl Write,
+        maze: &Maze,
+        steps: &[GenerationStep],
+    ) -> io::Result<()> {
+        write!(out, "{CLEAR}")?;
+        let mut walls = vec![vec![true; maze.width]; maze.height];
+        self.show(out, &render_walls(&walls))?;
+
+        for (i, step) in steps.iter().enumerate() {
+            walls[step.point.y][step.point.x] = step.obsticle;
+            if self.frame_due(i, steps.len()) {
+                self.show(out, &render_walls(&walls))?;
+            }
+        }
+
+        self.show(out, &render_search(maze, &SearchFrame::default()))
+    }
+
+    // shows the frontier and the expanded points growing, then the path
+    pub fn search(
+        &self,
+        out: &mut impl Write,
+        maze: &Maze,
+        steps: &[SearchStep],
+    ) -> io::Result<()> {
+        write!(out, "{CLEAR}")?;
+        let mut frame = SearchFrame::default();
+        self.show(out, &render_search(maze, &frame))?;
+
+        for (i, step) in steps.iter().enumerate() {
+            frame.apply(step);
+            if self.frame_due(i, steps.len()) || matches!(step, SearchStep::Path(_)) {
+                self.show(out, &render_search(maze, &frame))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        animation::{generation_frames, search_frames, Animation, SearchFrame, HOME},
+        pathfinding::{AStar, SearchStep, ALL},
+        Maze, MazeAlgorithm,
+    };
+
+    #[test]
+    fn search_steps_test() {
+        let maze = Maze::generate_seeded(15, 21, 3).unwrap();
+        for pathfinder in ALL {
+            let result = pathfinder.search(&maze).unwrap();
+            let steps = maze.search_steps(pathfinder);
+
+            let mut frame = SearchFrame::default();
+            for step in steps.iter() {
+                frame.apply(step);
+            }
+            assert_eq!(frame.expanded, result.expanded, "{}", pathfinder.name());
+            assert_eq!(frame.path, result.path, "{}", pathfinder.name());
+            assert_eq!(steps.first(), Some(&SearchStep::Frontier(maze.start)));
+
+            // nothing is expanded before it was on the frontier
+            for (i, step) in steps.iter().enumerate() {
+                if let SearchStep::Expand(point) = step {
+                    assert!(steps[..i].contains(&SearchStep::Frontier(*point)));
+                }
+            }
+        }
+    }
+    #[test]
+    fn frames_test() {
+        let maze = Maze::generate_seeded(15, 21, 3).unwrap();
+        let steps = maze.search_steps(&AStar);
+        let frames: Vec<SearchFrame> = search_frames(&steps).collect();
+        assert_eq!(frames.len(), steps.len());
+        assert!(frames
+            .windows(2)
+            .all(|pair| pair[0].expanded.len() <= pair[1].expanded.len()));
+
+        for algorithm in MazeAlgorithm::ALL {
+            let (maze, steps) = Maze::builder(15, 21)
+                .algorithm(algorithm)
+                .seed(3)
+                .braid(0.5)
+                .build_steps()
+                .unwrap();
+            let last = generation_frames(15, 21, &steps).last().unwrap();
+            let walls: Vec<Vec<bool>> = maze
+                .grid
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.obsticle).collect())
+                .collect();
+            assert_eq!(last, walls, "{}", algorithm.name());
+        }
+    }
+    #[test]
+    fn animation_test() {
+        let (maze, generation) = Maze::builder(9, 9).seed(3).build_steps().unwrap();
+        let search = maze.search_steps(&AStar);
+
+        let mut animation = Animation::new(Duration::ZERO);
+        let mut out = vec![];
+        animation.generation(&mut out, &maze, &generation).unwrap();
+        let frames = String::from_utf8(out).unwrap().matches(HOME).count();
+        // all walls, every step, the finished maze
+        assert_eq!(frames, generation.len() + 2);
+
+        animation.steps_per_frame = 10;
+        let mut out = vec![];
+        animation.search(&mut out, &maze, &search).unwrap();
+        let frames = String::from_utf8(out).unwrap().matches(HOME).count();
+        assert_eq!(frames, 1 + search.len().div_ceil(10));
+    }
+}
@@ -0,0 +1,380 @@
+// Options of the maze_generator binary, parsed by hand from the command line.
+
+use std::{
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use crate::{
+    animation::{render_search, Animation, SearchFrame},
+    format, pathfinding,
+    play::Game,
+    solvers, Maze, MazeAlgorithm, MazeBuilder, Pathfinder,
+};
+
+pub const USAGE: &str = "\
+usage: maze_generator [play] [OPTIONS]
+
+  play                   walk the maze with the arrow keys instead of printing it
+
+  -s, --size HxW         height and width in cells, 20x20 by default
+      --seed N           same seed, size and algorithm give the same maze
+  -a, --algorithm NAME   prim, backtracker, kruskal, wilson, eller, aldous-broder,
+                         binary-tree, sidewinder or division
+      --braid F          share of the dead ends to remove
+      --loops F          share of the walls between two open cells to knock out
+      --terrain F        share of the open cells covered with mud and water
+      --solver NAME      a*, bfs, dfs, dijkstra, greedy, bidirectional, jps, left-hand,
+                         right-hand, tremaux, dead-end-filling, pledge or all
+  -f, --format FORMAT    ansi, ascii, json or png, ansi by default
+      --cell-size N      pixels per cell of a png, 8 by default
+      --animate          replay the generation and the search before the output
+  -o, --output FILE      write the maze to a file instead of the terminal
+  -h, --help             print this help
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ansi,
+    Ascii,
+    Json,
+    Png,
+}
+
+impl Format {
+    pub const ALL: [Self; 4] = [Self::Ansi, Self::Ascii, Self::Json, Self::Png];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ansi => "ansi",
+            Self::Ascii => "ascii",
+            Self::Json => "json",
+            Self::Png => "png",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub play: bool,
+    pub help: bool,
+    pub height: usize,
+    pub width: usize,
+    pub seed: Option<u64>,
+    pub algorithm: MazeAlgorithm,
+    pub braid: f64,
+    pub loops: f64,
+    pub terrain: f64,
+    // a pathfinder name or all
+    pub solver: Option<String>,
+    pub format: Format,
+    pub cell_size: usize,
+    pub animate: bool,
+    pub output: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            play: false,
+            help: false,
+            height: 20,
+            width: 20,
+            seed: None,
+            algorithm: MazeAlgorithm::default(),
+            braid: 0.0,
+            loops: 0.0,
+            terrain: 0.0,
+            solver: None,
+            format: Format::Ansi,
+            cell_size: 8,
+            animate: false,
+            output: None,
+        }
+    }
+}
+
+fn find<T: Copy>(
+    all: &[T],
+    name: &str,
+    what: &str,
+    named: impl Fn(&T) -> &str,
+) -> Result<T, String> {
+    all.iter()
+        .find(|item| named(item) == name)
+        .copied()
+        .ok_or_else(|| format!("unknown {what} '{name}'"))
+}
+
+// the searches and the walking solvers
+fn solvers() -> Vec<&'static dyn Pathfinder> {
+    pathfinding::ALL.into_iter().chain(solvers::ALL).collect()
+}
+
+fn number<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{option} expects a number, got '{value}'"))
+}
+
+impl Options {
+    // the arguments without the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            // --name=value is the same as --name value
+            let (option, inline) = match arg.split_once('=') {
+                Some((option, value)) if option.starts_with("--") => {
+                    (option.to_string(), Some(value.to_string()))
+                }
+                _ => (arg, None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{option} expects a value"))
+            };
+
+            match option.as_str() {
+                "play" => options.play = true,
+                "-h" | "--help" => options.help = true,
+                "--animate" => options.animate = true,
+                "-s" | "--size" => {
+                    let size = value()?;
+                    let (height, width) = size
+                        .split_once(['x', 'X'])
+                        .ok_or_else(|| format!("{option} expects HEIGHTxWIDTH, got '{size}'"))?;
+                    options.height = number(&option, height)?;
+                    options.width = number(&option, width)?;
+                }
+                "--seed" => options.seed = Some(number(&option, &value()?)?),
+                "-a" | "--algorithm" => {
+                    options.algorithm =
+                        find(&MazeAlgorithm::ALL, &value()?, "algorithm", |a| a.name())?;
+                }
+                "--braid" => options.braid = number(&option, &value()?)?,
+                "--loops" => options.loops = number(&option, &value()?)?,
+                "--terrain" => options.terrain = number(&option, &value()?)?,
+                "--solver" => {
+                    let solver = value()?;
+                    if solver != "all" {
+                        find(&solvers(), &solver, "solver", |p| p.name())?;
+                    }
+                    options.solver = Some(solver);
+                }
+                "-f" | "--format" => {
+                    options.format = find(&Format::ALL, &value()?, "format", |f| f.name())?;
+                }
+                "--cell-size" => options.cell_size = number(&option, &value()?)?,
+                "-o" | "--output" => options.output = Some(PathBuf::from(value()?)),
+                _ => return Err(format!("unknown argument '{option}'")),
+            }
+        }
+
+        if options.cell_size == 0 {
+            return Err("--cell-size must be at least 1".into());
+        }
+        Ok(options)
+    }
+
+    pub fn pathfinders(&self) -> Vec<&'static dyn Pathfinder> {
+        match self.solver.as_deref() {
+            None => vec![],
+            Some("all") => solvers(),
+            Some(name) => solvers()
+                .into_iter()
+                .filter(|pathfinder| pathfinder.name() == name)
+                .collect(),
+        }
+    }
+
+    fn builder(&self) -> MazeBuilder {
+        let mut builder = Maze::builder(self.height, self.width)
+            .algorithm(self.algorithm)
+            .braid(self.braid)
+            .loops(self.loops)
+            .terrain(self.terrain);
+        if let Some(seed) = self.seed {
+            builder = builder.seed(seed);
+        }
+        builder
+    }
+
+    // the maze in the chosen format, the first pathfinder draws its search on ansi
+    pub fn render(&self, maze: &Maze) -> Result<Vec<u8>, format::Error> {
+        Ok(match self.format {
+            Format::Ansi => {
+                let mut frame = SearchFrame::default();
+                if let Some(result) = self.pathfinders().first().and_then(|p| maze.solve(*p)) {
+                    frame.expanded = result.expanded;
+                    frame.path = result.path;
+                }
+                render_search(maze, &frame).into_bytes()
+            }
+            Format::Ascii => maze.to_ascii().into_bytes(),
+            Format::Json => maze.to_json().into_bytes(),
+            Format::Png => maze.to_png(self.cell_size)?,
+        })
+    }
+
+    pub fn run(&self) -> Result<(), Box<dyn Error>> {
+        if self.help {
+            print!("{USAGE}");
+            return Ok(());
+        }
+        if self.play {
+            let mut game = Game::new(self.builder().build()?);
+            game.play()?;
+            match game.solved() {
+                true => println!("{}", game.summary()),
+                false => println!(
+                    "gave up after {} moves, seed {}",
+                    game.moves, game.maze.seed
+                ),
+            }
+            return Ok(());
+        }
+
+        let (maze, generation) = self.builder().build_steps()?;
+        let pathfinders = self.pathfinders();
+
+        if self.animate {
+            let animation = Animation {
+                delay: Duration::from_millis(10),
+                steps_per_frame: (maze.height * maze.width / 400).max(1),
+            };
+            let mut out = io::stdout();
+            animation.generation(&mut out, &maze, &generation)?;
+            if let Some(pathfinder) = pathfinders.first() {
+                animation.search(&mut out, &maze, &maze.search_steps(*pathfinder))?;
+            }
+            println!();
+        }
+
+        let output = self.render(&maze)?;
+        match &self.output {
+            Some(path) => fs::write(path, output)?,
+            None => io::stdout().write_all(&output)?,
+        }
+
+        // on stderr, so the maze on stdout can be piped
+        eprintln!("seed: {}", maze.seed);
+        for pathfinder in pathfinders {
+            if !pathfinder.supports(&maze) {
+                eprintln!("{:<18}can't search this maze", pathfinder.name());
+                continue;
+            }
+            match maze.solve(pathfinder) {
+                Some(result) => eprintln!(
+                    "{:<18}cost: {:<8}expanded: {}",
+                    pathfinder.name(),
+                    result.cost,
+                    result.expanded.len()
+                ),
+                None => eprintln!("{:<18}no path", pathfinder.name()),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{
+        cli::{Format, Options},
+        Maze, MazeAlgorithm,
+    };
+
+    fn parse(line: &str) -> Result<Options, String> {
+        Options::parse(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(parse("").unwrap(), Options::default());
+
+        let options = parse(
+            "-s 15x31 --seed 7 -a wilson --braid 0.5 --solver jps -f png --cell-size=4 --animate -o maze.png",
+        )
+        .unwrap();
+        assert_eq!((options.height, options.width), (15, 31));
+        assert_eq!(options.seed, Some(7));
+        assert_eq!(options.algorithm, MazeAlgorithm::Wilson);
+        assert_eq!(options.braid, 0.5);
+        assert_eq!(options.solver.as_deref(), Some("jps"));
+        assert_eq!(options.pathfinders().len(), 1);
+        assert_eq!(options.format, Format::Png);
+        assert_eq!(options.cell_size, 4);
+        assert!(options.animate && !options.play);
+        assert_eq!(options.output, Some(PathBuf::from("maze.png")));
+
+        let options = parse("play --size=9X9 --solver all").unwrap();
+        assert!(options.play);
+        assert_eq!((options.height, options.width), (9, 9));
+        assert_eq!(options.pathfinders().len(), 12);
+        assert_eq!(parse("--solver pledge").unwrap().pathfinders().len(), 1);
+        assert!(parse("--help").unwrap().help);
+    }
+    #[test]
+    fn parse_error_test() {
+        assert_eq!(parse("--size"), Err("--size expects a value".into()));
+        assert_eq!(
+            parse("--size 20"),
+            Err("--size expects HEIGHTxWIDTH, got '20'".into())
+        );
+        assert_eq!(
+            parse("--seed abc"),
+            Err("--seed expects a number, got 'abc'".into())
+        );
+        assert_eq!(parse("-a maze"), Err("unknown algorithm 'maze'".into()));
+        assert_eq!(parse("--solver a"), Err("unknown solver 'a'".into()));
+        assert_eq!(parse("-f bmp"), Err("unknown format 'bmp'".into()));
+        assert_eq!(
+            parse("--verbose"),
+            Err("unknown argument '--verbose'".into())
+        );
+        assert!(parse("--cell-size 0").is_err());
+    }
+    #[test]
+    fn render_test() {
+        let mut options = parse("--seed 3 -s 11x15 -a kruskal").unwrap();
+        let maze = options.builder().build().unwrap();
+        assert_eq!(
+            maze,
+            Maze::builder(11, 15)
+                .seed(3)
+                .algorithm(MazeAlgorithm::Kruskal)
+                .build()
+                .unwrap()
+        );
+
+        options.format = Format::Ascii;
+        assert_eq!(options.render(&maze).unwrap(), maze.to_ascii().into_bytes());
+        options.format = Format::Json;
+        let json = String::from_utf8(options.render(&maze).unwrap()).unwrap();
+        assert_eq!(Maze::from_json(&json).unwrap(), maze);
+        options.format = Format::Png;
+        assert_eq!(
+            Maze::from_png(&options.render(&maze).unwrap(), 8)
+                .unwrap()
+                .grid,
+            maze.grid
+        );
+
+        // the ansi maze has two characters and an escape sequence per cell
+        options.format = Format::Ansi;
+        let plain = options.render(&maze).unwrap();
+        options.solver = Some("a*".into());
+        let solved = options.render(&maze).unwrap();
+        assert_eq!(plain.len(), solved.len());
+        assert_ne!(plain, solved);
+    }
+}
@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+use crate::{MazeAlgorithm, Point};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MazeError {
+    // point outside of the maze
+    OutOfBounds(Point),
+    // start or destanation on a wall
+    OnWall(Point),
+    // start and destanation on the same point
+    Overlaps(Point),
+    // fewer than two rooms, so the start and the destanation can't be apart
+    TooSmall { height: usize, width: usize },
+    // a passage between two cells that don't touch
+    NotNeighbours(Point, Point),
+    // an algorithm that only carves square grids, on the named topology
+    Unsupported(MazeAlgorithm, &'static str),
+}
+
+impl Display for MazeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfBounds(point) => write!(f, "{point} is out of bounds"),
+            Self::OnWall(point) => write!(f, "{point} is on a wall"),
+            Self::Overlaps(point) => write!(f, "start and destanation overlap at {point}"),
+            Self::TooSmall { height, width } => write!(f, "{height}x{width} maze is too small"),
+            Self::NotNeighbours(a, b) => write!(f, "{a} and {b} aren't neighbours"),
+            Self::Unsupported(algorithm, topology) => {
+                write!(f, "{} can't carve a {topology} grid", algorithm.name())
+            }
+        }
+    }
+}
+
+impl std::error::Error for MazeError {}
@@ -0,0 +1,659 @@
+// Reading and writing mazes: ASCII text, JSON, and PBM / PGM / PNG images where
+// every cell is a square of `cell_size` pixels.
+//
+// ASCII uses `#` for walls, `.` for road, `S` and `E` for the start and the destanation,
+// and `%` / `~` for mud and water. PBM only knows walls, PGM and PNG keep everything.
+// A start or destanation that is missing from the input falls back to the first and
+// the last open cell.
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Cell, Heuristic, Maze, Movement, Point, Terrain};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    // input isn't in the format it claims to be
+    Malformed(String),
+    // input is well formed but doesn't make a maze
+    Invalid(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed(reason) => write!(f, "malformed input: {reason}"),
+            Self::Invalid(reason) => write!(f, "invalid maze: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Wall,
+    Start,
+    Destanation,
+    Open(Terrain),
+}
+
+impl Tile {
+    const ALL: [Self; 6] = [
+        Self::Wall,
+        Self::Start,
+        Self::Destanation,
+        Self::Open(Terrain::Road),
+        Self::Open(Terrain::Mud),
+        Self::Open(Terrain::Water),
+    ];
+
+    fn symbol(&self) -> char {
+        match self {
+            Self::Wall => '#',
+            Self::Start => 'S',
+            Self::Destanation => 'E',
+            Self::Open(Terrain::Road) => '.',
+            Self::Open(Terrain::Mud) => '%',
+            Self::Open(Terrain::Water) => '~',
+        }
+    }
+
+    // same colours as the terminal
+    fn rgb(&self) -> [u8; 3] {
+        match self {
+            Self::Wall => [0, 0, 0],
+            Self::Start => [0, 205, 0],
+            Self::Destanation => [205, 0, 0],
+            Self::Open(Terrain::Road) => [255, 255, 255],
+            Self::Open(Terrain::Mud) => [175, 135, 95],
+            Self::Open(Terrain::Water) => [0, 95, 135],
+        }
+    }
+
+    fn grey(&self) -> u8 {
+        match self {
+            Self::Wall => 0,
+            Self::Start => 50,
+            Self::Open(Terrain::Water) => 100,
+            Self::Open(Terrain::Mud) => 150,
+            Self::Destanation => 200,
+            Self::Open(Terrain::Road) => 255,
+        }
+    }
+
+    // closest colour, so images that went through an editor still load
+    fn from_rgb(rgb: [u8; 3]) -> Self {
+        let distance = |tile: &Tile| -> u32 {
+            tile.rgb()
+                .iter()
+                .zip(rgb)
+                .map(|(&a, b)| (a as i32 - b as i32).pow(2) as u32)
+                .sum()
+        };
+        *Self::ALL.iter().min_by_key(|tile| distance(tile)).unwrap()
+    }
+
+    fn from_grey(grey: u8) -> Self {
+        *Self::ALL
+            .iter()
+            .min_by_key(|tile| tile.grey().abs_diff(grey))
+            .unwrap()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct MazeFile {
+    seed: u64,
+    movement: Movement,
+    heuristic: Heuristic,
+    // the ASCII rows
+    rows: Vec<String>,
+}
+
+impl Maze {
+    fn tiles(&self) -> Vec<Vec<Tile>> {
+        self.grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| {
+                        if cell.obsticle {
+                            Tile::Wall
+                        } else if cell.point == self.start {
+                            Tile::Start
+                        } else if cell.point == self.destanation {
+                            Tile::Destanation
+                        } else {
+                            Tile::Open(cell.terrain)
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn from_tiles(tiles: Vec<Vec<Tile>>) -> Result<Self, Error> {
+        let height = tiles.len();
+        let width = tiles.first().map_or(0, |row| row.len());
+        if height == 0 || width == 0 {
+            return Err(Error::Invalid("maze is empty".into()));
+        }
+        if tiles.iter().any(|row| row.len() != width) {
+            return Err(Error::Invalid("rows have different lengths".into()));
+        }
+
+        let mut grid = vec![];
+        let mut open = vec![];
+        let mut start = None;
+        let mut destanation = None;
+
+        for (y, row) in tiles.iter().enumerate() {
+            let mut cells = vec![];
+            for (x, tile) in row.iter().enumerate() {
+                let point = Point { x, y };
+                match tile {
+                    Tile::Start if start.is_some() => {
+                        return Err(Error::Invalid("more than one start".into()))
+                    }
+                    Tile::Destanation if destanation.is_some() => {
+                        return Err(Error::Invalid("more than one destanation".into()))
+                    }
+                    Tile::Start => start = Some(point),
+                    Tile::Destanation => destanation = Some(point),
+                    _ => {}
+                }
+                if *tile != Tile::Wall {
+                    open.push(point);
+                }
+                cells.push(Cell {
+                    point,
+                    obsticle: *tile == Tile::Wall,
+                    terrain: match tile {
+                        Tile::Open(terrain) => *terrain,
+                        _ => Terrain::default(),
+                    },
+                });
+            }
+            grid.push(cells);
+        }
+
+        let start = start
+            .or_else(|| {
+                open.iter()
+                    .find(|point| Some(**point) != destanation)
+                    .copied()
+            })
+            .ok_or_else(|| Error::Invalid("no open cell for the start".into()))?;
+        let destanation = destanation
+            .or_else(|| open.iter().rev().find(|point| **point != start).copied())
+            .ok_or_else(|| Error::Invalid("no open cell for the destanation".into()))?;
+
+        Ok(Self {
+            seed: 0,
+            height,
+            width,
+            start,
+            destanation,
+            grid,
+            movement: Movement::default(),
+            heuristic: Movement::default().heuristic(),
+        })
+    }
+
+    pub fn to_ascii(&self) -> String {
+        let mut result = String::new();
+        for row in self.tiles() {
+            result.extend(row.iter().map(Tile::symbol));
+            result.push('\n');
+        }
+        result
+    }
+
+    pub fn from_ascii(text: &str) -> Result<Self, Error> {
+        let tiles = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.chars()
+                    .map(|c| {
+                        Tile::ALL
+                            .into_iter()
+                            .find(|tile| tile.symbol() == c)
+                            .ok_or_else(|| Error::Malformed(format!("unknown cell '{c}'")))
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        Self::from_tiles(tiles)
+    }
+
+    pub fn to_json(&self) -> String {
+        let file = MazeFile {
+            seed: self.seed,
+            movement: self.movement,
+            heuristic: self.heuristic,
+            rows: self.to_ascii().lines().map(String::from).collect(),
+        };
+        serde_json::to_string_pretty(&file).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let file: MazeFile =
+            serde_json::from_str(json).map_err(|e| Error::Malformed(e.to_string()))?;
+        let mut maze = Self::from_ascii(&file.rows.join("\n"))?;
+        maze.seed = file.seed;
+        maze.movement = file.movement;
+        maze.heuristic = file.heuristic;
+        Ok(maze)
+    }
+
+    // every cell as `cell_size` x `cell_size` pixels, row by row
+    fn pixels<T: Copy>(&self, cell_size: usize, pixel: impl Fn(Tile) -> T) -> Vec<T> {
+        let mut pixels = vec![];
+        for row in self.tiles() {
+            for _ in 0..cell_size {
+                for tile in row.iter() {
+                    pixels.extend(std::iter::repeat_n(pixel(*tile), cell_size));
+                }
+            }
+        }
+        pixels
+    }
+
+    // width and height of the image with cell_size pixels a side for every cell
+    fn image_dimensions(&self, cell_size: usize) -> Result<(usize, usize), Error> {
+        let (width, height) = (self.width, self.height);
+        if cell_size == 0 || width == 0 || height == 0 {
+            return Err(Error::Invalid(format!(
+                "{width}x{height} maze with {cell_size}px cells makes an empty image"
+            )));
+        }
+        let scale = |cells: usize| cells.checked_mul(cell_size);
+        scale(width)
+            .zip(scale(height))
+            .filter(|(width, height)| width.checked_mul(*height).is_some())
+            .ok_or_else(|| Error::Invalid(format!("{cell_size}px cells are too large")))
+    }
+
+    // binary PBM, black pixels are walls
+    pub fn to_pbm(&self, cell_size: usize) -> Result<Vec<u8>, Error> {
+        let (width, height) = self.image_dimensions(cell_size)?;
+        let pixels = self.pixels(cell_size, |tile| tile == Tile::Wall);
+
+        let mut result = format!("P4\n{width} {height}\n").into_bytes();
+        for row in pixels.chunks(width) {
+            for byte in row.chunks(8) {
+                let bits = byte
+                    .iter()
+                    .enumerate()
+                    .fold(0, |bits, (i, &black)| bits | (black as u8) << (7 - i));
+                result.push(bits);
+            }
+        }
+        Ok(result)
+    }
+
+    // reads plain (P1) and binary (P4) PBM
+    pub fn from_pbm(data: &[u8], cell_size: usize) -> Result<Self, Error> {
+        let (magic, header, body) = netpbm_header(data, 2)?;
+        let (width, height) = (header[0], header[1]);
+        let size = image_size(width, height)?;
+
+        let pixels: Vec<bool> = match magic {
+            "P1" => body
+                .iter()
+                .filter(|byte| matches!(byte, b'0' | b'1'))
+                .map(|&byte| byte == b'1')
+                .collect(),
+            "P4" => body
+                .get(..width.div_ceil(8) * height)
+                .ok_or_else(|| Error::Malformed(format!("{} bytes for {size} pixels", body.len())))?
+                .chunks(width.div_ceil(8))
+                .flat_map(|row| {
+                    (0..width)
+                        .map(move |x| row.get(x / 8).is_some_and(|b| b >> (7 - x % 8) & 1 == 1))
+                })
+                .collect(),
+            _ => return Err(Error::Malformed(format!("{magic} isn't a PBM"))),
+        };
+
+        let tiles = sample(&pixels, width, height, cell_size, |&black| match black {
+            true => Tile::Wall,
+            false => Tile::Open(Terrain::Road),
+        })?;
+        Self::from_tiles(tiles)
+    }
+
+    // binary PGM with a grey level per kind of cell
+    pub fn to_pgm(&self, cell_size: usize) -> Result<Vec<u8>, Error> {
+        let (width, height) = self.image_dimensions(cell_size)?;
+        let mut result = format!("P5\n{width} {height}\n255\n").into_bytes();
+        result.extend(self.pixels(cell_size, |tile| tile.grey()));
+        Ok(result)
+    }
+
+    // reads plain (P2) and binary (P5) PGM
+    pub fn from_pgm(data: &[u8], cell_size: usize) -> Result<Self, Error> {
+        let (magic, header, body) = netpbm_header(data, 3)?;
+        let (width, height, max) = (header[0], header[1], header[2]);
+        // netpbm caps the grey levels at 16 bits
+        if !(1..=u16::MAX as usize).contains(&max) {
+            return Err(Error::Malformed(format!("maximum grey level {max}")));
+        }
+
+        let pixels: Vec<usize> = match magic {
+            "P2" => std::str::from_utf8(body)
+                .map_err(|_| Error::Malformed("PGM body isn't text".into()))?
+                .split_ascii_whitespace()
+                .map(|value| {
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|&grey| grey <= max)
+                        .ok_or_else(|| Error::Malformed(format!("bad grey level '{value}'")))
+                })
+                .collect::<Result<_, _>>()?,
+            "P5" if max < 256 => body.iter().map(|&grey| grey as usize).collect(),
+            "P5" => body
+                .chunks(2)
+                .map(|pair| (pair[0] as usize) << 8 | *pair.get(1).unwrap_or(&0) as usize)
+                .collect(),
+            _ => return Err(Error::Malformed(format!("{magic} isn't a PGM"))),
+        };
+
+        let tiles = sample(&pixels, width, height, cell_size, |&grey| {
+            Tile::from_grey((grey * 255 / max).min(255) as u8)
+        })?;
+        Self::from_tiles(tiles)
+    }
+
+    pub fn to_png(&self, cell_size: usize) -> Result<Vec<u8>, Error> {
+        let (width, height) = self.image_dimensions(cell_size)?;
+        // png stores the dimensions in 32 bits
+        let (width, height) = u32::try_from(width)
+            .ok()
+            .zip(u32::try_from(height).ok())
+            .ok_or_else(|| Error::Invalid(format!("{width}x{height} is too large for a PNG")))?;
+
+        let mut result = vec![];
+        let mut encoder = png::Encoder::new(&mut result, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let pixels: Vec<u8> = self.pixels(cell_size, |tile| tile.rgb()).concat();
+        let invalid = |e: png::EncodingError| Error::Invalid(e.to_string());
+        let mut writer = encoder.write_header().map_err(invalid)?;
+        writer.write_image_data(&pixels).map_err(invalid)?;
+        writer.finish().map_err(invalid)?;
+
+        Ok(result)
+    }
+
+    pub fn from_png(data: &[u8], cell_size: usize) -> Result<Self, Error> {
+        let mut decoder = png::Decoder::new(data);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder
+            .read_info()
+            .map_err(|e| Error::Malformed(e.to_string()))?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut buffer)
+            .map_err(|e| Error::Malformed(e.to_string()))?;
+
+        let channels = info.color_type.samples();
+        let pixels: Vec<[u8; 3]> = buffer[..info.buffer_size()]
+            .chunks(channels)
+            .map(|pixel| match channels {
+                // grey, grey with alpha
+                1 | 2 => [pixel[0]; 3],
+                _ => [pixel[0], pixel[1], pixel[2]],
+            })
+            .collect();
+
+        let tiles = sample(
+            &pixels,
+            info.width as usize,
+            info.height as usize,
+            cell_size,
+            |&rgb| Tile::from_rgb(rgb),
+        )?;
+        Self::from_tiles(tiles)
+    }
+}
+
+// splits a netpbm file into its magic number, the header numbers and the pixel data
+fn netpbm_header(data: &[u8], count: usize) -> Result<(&str, Vec<usize>, &[u8]), Error> {
+    let magic = data
+        .get(..2)
+        .and_then(|magic| std::str::from_utf8(magic).ok())
+        .ok_or_else(|| Error::Malformed("missing magic number".into()))?;
+
+    let mut numbers = vec![];
+    let mut i = 2;
+    while numbers.len() < count {
+        match data.get(i) {
+            None => return Err(Error::Malformed("header ends early".into())),
+            Some(b'#') => {
+                while data.get(i).is_some_and(|&byte| byte != b'\n') {
+                    i += 1;
+                }
+            }
+            Some(byte) if byte.is_ascii_whitespace() => i += 1,
+            Some(byte) if byte.is_ascii_digit() => {
+                let start = i;
+                while data.get(i).is_some_and(u8::is_ascii_digit) {
+                    i += 1;
+                }
+                let number = std::str::from_utf8(&data[start..i]).unwrap();
+                numbers.push(
+                    number
+                        .parse()
+                        .map_err(|_| Error::Malformed(format!("bad header number {number}")))?,
+                );
+            }
+            Some(byte) => {
+                return Err(Error::Malformed(format!(
+                    "unexpected '{}' in header",
+                    *byte as char
+                )))
+            }
+        }
+    }
+
+    // a single whitespace separates the header from binary pixels
+    Ok((magic, numbers, data.get(i + 1..).unwrap_or_default()))
+}
+
+// the pixel count of an image, which can't be empty
+fn image_size(width: usize, height: usize) -> Result<usize, Error> {
+    if width == 0 || height == 0 {
+        return Err(Error::Invalid(format!("{width}x{height} image is empty")));
+    }
+    width
+        .checked_mul(height)
+        .ok_or_else(|| Error::Malformed(format!("{width}x{height} image is too large")))
+}
+
+// reads the middle pixel of every cell
+fn sample<T>(
+    pixels: &[T],
+    width: usize,
+    height: usize,
+    cell_size: usize,
+    tile: impl Fn(&T) -> Tile,
+) -> Result<Vec<Vec<Tile>>, Error> {
+    if cell_size == 0 || !width.is_multiple_of(cell_size) || !height.is_multiple_of(cell_size) {
+        return Err(Error::Invalid(format!(
+            "{width}x{height} image isn't made of {cell_size}px cells"
+        )));
+    }
+    if pixels.len() < image_size(width, height)? {
+        return Err(Error::Malformed(format!(
+            "{} pixels for a {width}x{height} image",
+            pixels.len()
+        )));
+    }
+
+    let middle = cell_size / 2;
+    Ok((0..height / cell_size)
+        .map(|y| {
+            (0..width / cell_size)
+                .map(|x| tile(&pixels[(y * cell_size + middle) * width + x * cell_size + middle]))
+                .collect()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{format::Error, Maze, Movement, Point, Terrain};
+
+    const HAND_MADE: &str = "\
+S..#....
+##.#.##.
+.%.~..#E
+";
+
+    fn sample() -> Maze {
+        Maze::builder(15, 21)
+            .seed(12)
+            .braid(0.5)
+            .terrain(0.3)
+            .movement(Movement::EightWay {
+                corner_cutting: true,
+            })
+            .build()
+            .unwrap()
+    }
+
+    // what survives a format without seed and search settings
+    fn same_layout(a: &Maze, b: &Maze) {
+        assert_eq!(a.grid, b.grid);
+        assert_eq!((a.start, a.destanation), (b.start, b.destanation));
+    }
+
+    #[test]
+    fn ascii_test() {
+        let maze = Maze::from_ascii(HAND_MADE).unwrap();
+        assert_eq!((maze.height, maze.width), (3, 8));
+        assert_eq!(maze.start, Point { x: 0, y: 0 });
+        assert_eq!(maze.destanation, Point { x: 7, y: 2 });
+        assert!(maze.grid[0][3].obsticle);
+        assert_eq!(maze.grid[2][1].terrain, Terrain::Mud);
+        assert_eq!(maze.grid[2][3].terrain, Terrain::Water);
+        assert_eq!(maze.to_ascii(), HAND_MADE);
+
+        let maze = sample();
+        same_layout(&maze, &Maze::from_ascii(&maze.to_ascii()).unwrap());
+    }
+    #[test]
+    fn json_test() {
+        let maze = sample();
+        let json = maze.to_json();
+        println!("{json}");
+        assert_eq!(Maze::from_json(&json).unwrap(), maze);
+    }
+    #[test]
+    fn image_test() {
+        let maze = sample();
+        for cell_size in [1, 4, 5] {
+            same_layout(
+                &maze,
+                &Maze::from_png(&maze.to_png(cell_size).unwrap(), cell_size).unwrap(),
+            );
+            same_layout(
+                &maze,
+                &Maze::from_pgm(&maze.to_pgm(cell_size).unwrap(), cell_size).unwrap(),
+            );
+
+            // PBM only keeps the walls
+            let pbm = Maze::from_pbm(&maze.to_pbm(cell_size).unwrap(), cell_size).unwrap();
+            let walls = |maze: &Maze| -> Vec<bool> {
+                maze.grid
+                    .iter()
+                    .flatten()
+                    .map(|cell| cell.obsticle)
+                    .collect()
+            };
+            assert_eq!(walls(&pbm), walls(&maze));
+        }
+        assert_eq!(&maze.to_png(4).unwrap()[1..4], b"PNG");
+        assert!(matches!(
+            Maze::from_png(&maze.to_png(4).unwrap(), 8),
+            Err(Error::Invalid(_))
+        ));
+
+        // no pixels or too many of them
+        for cell_size in [0, usize::MAX / 2, 1 << 30] {
+            for image in [maze.to_pbm(cell_size), maze.to_pgm(cell_size)] {
+                assert!(matches!(image, Err(Error::Invalid(_))), "{cell_size}");
+            }
+        }
+        for cell_size in [0, 1 << 30] {
+            assert!(matches!(maze.to_png(cell_size), Err(Error::Invalid(_))));
+        }
+    }
+    #[test]
+    fn plain_netpbm_test() {
+        let pbm = b"P1\n# a hand made maze\n4 3\n0 0 1 0\n1 0 1 0\n0 0 0 0\n";
+        let maze = Maze::from_pbm(pbm, 1).unwrap();
+        assert_eq!(maze.to_ascii(), "S.#.\n#.#.\n...E\n");
+
+        let pgm = b"P2\n3 1\n15\n3 0 12\n";
+        let maze = Maze::from_pgm(pgm, 1).unwrap();
+        assert_eq!(maze.to_ascii(), "S#E\n");
+    }
+    #[test]
+    fn invalid_test() {
+        assert!(matches!(
+            Maze::from_ascii("S.x\n"),
+            Err(Error::Malformed(_))
+        ));
+        assert!(matches!(
+            Maze::from_ascii("S.S.E\n"),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(
+            Maze::from_ascii("S..\n..\n"),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(
+            Maze::from_ascii("##\n#.\n"),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(Maze::from_json("{}"), Err(Error::Malformed(_))));
+        assert!(matches!(
+            Maze::from_pbm(b"P4\n8", 1),
+            Err(Error::Malformed(_))
+        ));
+
+        // headers that don't fit in memory or describe no image
+        assert!(matches!(
+            Maze::from_pbm(b"P4\n0 0\n", 1),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(
+            Maze::from_pbm(b"P1\n99999999999 99999999999\n0 1 0\n", 1),
+            Err(Error::Malformed(_))
+        ));
+        assert!(matches!(
+            Maze::from_pbm(b"P4\n999999999999999 1\n\xff", 1),
+            Err(Error::Malformed(_))
+        ));
+        assert!(matches!(
+            Maze::from_pgm(b"P2\n1 1\n70000\n0\n", 1),
+            Err(Error::Malformed(_))
+        ));
+        assert!(matches!(
+            Maze::from_pgm(b"P2\n1 1\n255\n99999999999999999\n", 1),
+            Err(Error::Malformed(_))
+        ));
+        assert!(matches!(
+            Maze::from_pgm(b"P5\n1 1\n0\n\0", 1),
+            Err(Error::Malformed(_))
+        ));
+    }
+}
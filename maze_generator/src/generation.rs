@@ -0,0 +1,960 @@
+use std::collections::HashSet;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{Cell, Point};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MazeAlgorithm {
+    // randomized Prim's, grows the maze from a random frontier cell
+    #[default]
+    Prim,
+    // depth first search, long winding corridors
+    RecursiveBacktracker,
+    // random edges joined by union-find, lots of short dead ends
+    Kruskal,
+    // loop-erased random walks, uniform spanning tree
+    Wilson,
+    // row by row with sets, constant memory per row
+    Eller,
+    // random walk carving unvisited cells, uniform spanning tree
+    AldousBroder,
+    // every cell carves north or west, diagonal bias
+    BinaryTree,
+    // runs along a row closed by a carve north, vertical bias
+    Sidewinder,
+    // starts empty and adds walls with a single gap, boxy chambers
+    RecursiveDivision,
+}
+
+impl MazeAlgorithm {
+    pub const ALL: [Self; 9] = [
+        Self::Prim,
+        Self::RecursiveBacktracker,
+        Self::Kruskal,
+        Self::Wilson,
+        Self::Eller,
+        Self::AldousBroder,
+        Self::BinaryTree,
+        Self::Sidewinder,
+        Self::RecursiveDivision,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Prim => "prim",
+            Self::RecursiveBacktracker => "backtracker",
+            Self::Kruskal => "kruskal",
+            Self::Wilson => "wilson",
+            Self::Eller => "eller",
+            Self::AldousBroder => "aldous-broder",
+            Self::BinaryTree => "binary-tree",
+            Self::Sidewinder => "sidewinder",
+            Self::RecursiveDivision => "division",
+        }
+    }
+
+    // recursive division splits rectangles, the others only need the neighbours of a cell
+    pub fn any_topology(&self) -> bool {
+        *self != Self::RecursiveDivision
+    }
+
+    // carves passages into a grid full of obsticles, returns every change in order
+    pub(crate) fn carve(&self, grid: &mut [Vec<Cell>], rng: &mut impl Rng) -> Vec<GenerationStep> {
+        let mut steps = vec![];
+        if *self == Self::Prim {
+            prim(grid, &mut steps, rng);
+            return steps;
+        }
+
+        let mut rooms = Rooms::new(grid, &mut steps);
+        match self {
+            Self::RecursiveDivision => recursive_division(&mut rooms, rng),
+            _ => self.carve_cells(&mut rooms, rng),
+        }
+        steps
+    }
+
+    // links the cells of any topology into a spanning tree, see any_topology
+    pub(crate) fn carve_cells(&self, cells: &mut impl Carve, rng: &mut impl Rng) {
+        match self {
+            Self::Prim => grow(cells, rng),
+            Self::RecursiveBacktracker => recursive_backtracker(cells, rng),
+            Self::Kruskal => kruskal(cells, rng),
+            Self::Wilson => wilson(cells, rng),
+            Self::Eller => eller(cells, rng),
+            Self::AldousBroder => aldous_broder(cells, rng),
+            Self::BinaryTree => binary_tree(cells, rng),
+            Self::Sidewinder => sidewinder(cells, rng),
+            Self::RecursiveDivision => unreachable!("recursive division needs a rectangle"),
+        }
+    }
+}
+
+// What the algorithms carve: the rooms of the block grid or the cells of a topology.
+// Rooms are in rows by their y, the ones next to each other in a row are neighbours
+pub(crate) trait Carve {
+    fn rows(&self) -> Vec<Vec<Point>>;
+    fn neighbors(&self, room: &Point) -> Vec<Point>;
+    fn index(&self, room: &Point) -> usize;
+    // part of the maze already
+    fn is_open(&self, room: &Point) -> bool;
+    fn open(&mut self, room: &Point);
+    // opens both rooms and the passage between them
+    fn link(&mut self, a: &Point, b: &Point);
+
+    fn points(&self) -> Vec<Point> {
+        self.rows().concat()
+    }
+
+    fn len(&self) -> usize {
+        self.points().len()
+    }
+
+    fn random(&self, rng: &mut impl Rng) -> Point {
+        *self.points().choose(rng).unwrap()
+    }
+}
+
+// a cell turning into a wall or a passage, in the order generation did it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenerationStep {
+    pub point: Point,
+    pub obsticle: bool,
+}
+
+// sets a cell and records the change
+fn set(
+    grid: &mut [Vec<Cell>],
+    steps: &mut Vec<GenerationStep>,
+    x: usize,
+    y: usize,
+    obsticle: bool,
+) {
+    if grid[y][x].obsticle != obsticle {
+        grid[y][x].obsticle = obsticle;
+        steps.push(GenerationStep {
+            point: Point { x, y },
+            obsticle,
+        });
+    }
+}
+
+// Rooms sit on even coordinates of the grid, the odd cells between two rooms are
+// the walls that get knocked out. Points handed to Rooms are in room coordinates.
+struct Rooms<'a> {
+    grid: &'a mut [Vec<Cell>],
+    steps: &'a mut Vec<GenerationStep>,
+    height: usize,
+    width: usize,
+}
+
+impl<'a> Rooms<'a> {
+    fn new(grid: &'a mut [Vec<Cell>], steps: &'a mut Vec<GenerationStep>) -> Self {
+        let height = grid.len().div_ceil(2);
+        let width = grid.first().map_or(0, |row| row.len().div_ceil(2));
+        Self {
+            grid,
+            steps,
+            height,
+            width,
+        }
+    }
+
+    // sets the wall between two adjacent rooms
+    fn set_wall(&mut self, a: &Point, b: &Point, obsticle: bool) {
+        set(self.grid, self.steps, a.x + b.x, a.y + b.y, obsticle);
+    }
+}
+
+impl Carve for Rooms<'_> {
+    fn rows(&self) -> Vec<Vec<Point>> {
+        (0..self.height)
+            .map(|y| (0..self.width).map(|x| Point { x, y }).collect())
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.height * self.width
+    }
+
+    fn random(&self, rng: &mut impl Rng) -> Point {
+        Point {
+            x: rng.gen_range(0..self.width),
+            y: rng.gen_range(0..self.height),
+        }
+    }
+
+    fn index(&self, room: &Point) -> usize {
+        room.y * self.width + room.x
+    }
+
+    fn neighbors(&self, room: &Point) -> Vec<Point> {
+        let mut rooms = vec![];
+        if room.y > 0 {
+            rooms.push(Point {
+                x: room.x,
+                y: room.y - 1,
+            });
+        }
+        if room.x + 1 < self.width {
+            rooms.push(Point {
+                x: room.x + 1,
+                y: room.y,
+            });
+        }
+        if room.y + 1 < self.height {
+            rooms.push(Point {
+                x: room.x,
+                y: room.y + 1,
+            });
+        }
+        if room.x > 0 {
+            rooms.push(Point {
+                x: room.x - 1,
+                y: room.y,
+            });
+        }
+        rooms
+    }
+
+    fn is_open(&self, room: &Point) -> bool {
+        !self.grid[room.y * 2][room.x * 2].obsticle
+    }
+
+    fn open(&mut self, room: &Point) {
+        set(self.grid, self.steps, room.x * 2, room.y * 2, false);
+    }
+
+    fn link(&mut self, a: &Point, b: &Point) {
+        self.open(a);
+        self.open(b);
+        self.set_wall(a, b, false);
+    }
+}
+
+fn prim(grid: &mut [Vec<Cell>], steps: &mut Vec<GenerationStep>, rng: &mut impl Rng) {
+    fn mark(
+        x: usize,
+        y: usize,
+        grid: &mut [Vec<Cell>],
+        steps: &mut Vec<GenerationStep>,
+        frontiers: &mut Vec<Cell>,
+    ) {
+        set(grid, steps, x, y, false);
+
+        if y >= 2 && !frontiers.contains(&grid[y - 2][x]) && grid[y - 2][x].obsticle {
+            let frontier = grid[y - 2][x];
+            // println!("North frontier: {}", frontier);
+            frontiers.push(frontier);
+        }
+        if y + 2 < grid.len() && !frontiers.contains(&grid[y + 2][x]) && grid[y + 2][x].obsticle {
+            let frontier = grid[y + 2][x];
+            // println!("South frontier: {}", frontier);
+            frontiers.push(frontier);
+        }
+        if x >= 2 && !frontiers.contains(&grid[y][x - 2]) && grid[y][x - 2].obsticle {
+            let frontier = grid[y][x - 2];
+            // println!("West frontier: {}", frontier);
+            frontiers.push(frontier);
+        }
+        if x + 2 < grid[0].len() && !frontiers.contains(&grid[y][x + 2]) && grid[y][x + 2].obsticle
+        {
+            let frontier = grid[y][x + 2];
+            // println!("East frontier: {}", frontier);
+            frontiers.push(frontier);
+        }
+    }
+
+    let height = grid.len();
+    let width = grid[0].len();
+
+    let x = rng.gen_range(0..width);
+    let y = rng.gen_range(0..height);
+
+    let mut frontiers: Vec<Cell> = vec![];
+
+    mark(x, y, grid, steps, &mut frontiers);
+
+    //  0
+    //3 x 1
+    //  2
+
+    while !frontiers.is_empty() {
+        let mut possible_to_crave = false;
+
+        let cell_index = rng.gen_range(0..frontiers.len());
+
+        let x = frontiers[cell_index].point.x;
+        let y = frontiers[cell_index].point.y;
+
+        frontiers.remove(cell_index);
+
+        while !possible_to_crave {
+            let direction = rng.gen_range(0..4);
+            if direction == 0 {
+                if y >= 2 && !grid[y - 2][x].obsticle {
+                    possible_to_crave = true;
+                    set(grid, steps, x, y - 1, false);
+                }
+            } else if direction == 1 {
+                if x + 2 < width && !grid[y][x + 2].obsticle {
+                    possible_to_crave = true;
+                    set(grid, steps, x + 1, y, false);
+                }
+            } else if direction == 2 {
+                if y + 2 < height && !grid[y + 2][x].obsticle {
+                    possible_to_crave = true;
+                    set(grid, steps, x, y + 1, false);
+                }
+            } else if x >= 2 && !grid[y][x - 2].obsticle {
+                possible_to_crave = true;
+                set(grid, steps, x - 1, y, false);
+            }
+        }
+
+        mark(x, y, grid, steps, &mut frontiers);
+    }
+}
+
+// randomized Prim's on any topology, the block grid keeps its own version above
+fn grow(rooms: &mut impl Carve, rng: &mut impl Rng) {
+    let start = rooms.random(rng);
+    rooms.open(&start);
+
+    let mut frontier = rooms.neighbors(&start);
+
+    while !frontier.is_empty() {
+        let room = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+
+        let inside: Vec<Point> = rooms
+            .neighbors(&room)
+            .into_iter()
+            .filter(|neighbor| rooms.is_open(neighbor))
+            .collect();
+        rooms.link(inside.choose(rng).unwrap(), &room);
+
+        for neighbor in rooms.neighbors(&room) {
+            if !rooms.is_open(&neighbor) && !frontier.contains(&neighbor) {
+                frontier.push(neighbor);
+            }
+        }
+    }
+}
+
+fn recursive_backtracker(rooms: &mut impl Carve, rng: &mut impl Rng) {
+    let start = rooms.random(rng);
+    rooms.open(&start);
+
+    let mut stack = vec![start];
+
+    while let Some(current) = stack.last().copied() {
+        let unvisited: Vec<Point> = rooms
+            .neighbors(&current)
+            .into_iter()
+            .filter(|room| !rooms.is_open(room))
+            .collect();
+
+        match unvisited.choose(rng) {
+            Some(next) => {
+                rooms.link(&current, next);
+                stack.push(*next);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+}
+
+fn kruskal(rooms: &mut impl Carve, rng: &mut impl Rng) {
+    fn find(sets: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while sets[root] != root {
+            root = sets[root];
+        }
+        // path compression
+        let mut i = i;
+        while sets[i] != root {
+            (sets[i], i) = (root, sets[i]);
+        }
+        root
+    }
+
+    let mut edges = vec![];
+
+    for room in rooms.points() {
+        rooms.open(&room);
+        for neighbor in rooms.neighbors(&room) {
+            // every edge once, towards the later room
+            if rooms.index(&neighbor) > rooms.index(&room) {
+                edges.push((room, neighbor));
+            }
+        }
+    }
+
+    edges.shuffle(rng);
+
+    let mut sets: Vec<usize> = (0..rooms.len()).collect();
+
+    for (a, b) in edges {
+        let set_a = find(&mut sets, rooms.index(&a));
+        let set_b = find(&mut sets, rooms.index(&b));
+        if set_a != set_b {
+            sets[set_a] = set_b;
+            rooms.link(&a, &b);
+        }
+    }
+}
+
+fn wilson(rooms: &mut impl Carve, rng: &mut impl Rng) {
+    let mut remaining = rooms.points();
+    remaining.shuffle(rng);
+
+    let mut in_maze = HashSet::new();
+
+    if let Some(first) = remaining.pop() {
+        rooms.open(&first);
+        in_maze.insert(first);
+    }
+
+    // direction taken from every room on the walk, later visits overwrite earlier
+    // ones which erases the loops
+    let mut next = vec![None; rooms.len()];
+
+    while let Some(start) = remaining.pop() {
+        if in_maze.contains(&start) {
+            continue;
+        }
+
+        let mut current = start;
+        while !in_maze.contains(&current) {
+            let step = *rooms.neighbors(&current).choose(rng).unwrap();
+            next[rooms.index(&current)] = Some(step);
+            current = step;
+        }
+
+        let mut current = start;
+        while !in_maze.contains(&current) {
+            let step = next[rooms.index(&current)].unwrap();
+            rooms.link(&current, &step);
+            in_maze.insert(current);
+            current = step;
+        }
+    }
+}
+
+fn eller(rooms: &mut impl Carve, rng: &mut impl Rng) {
+    // puts the set of b into the set of a
+    fn join(sets: &mut [Option<usize>], a: usize, b: usize) {
+        let (old, new) = (sets[b], sets[a]);
+        for set in sets.iter_mut() {
+            if *set == old {
+                *set = new;
+            }
+        }
+    }
+
+    let rows = rooms.rows();
+    let mut sets: Vec<Option<usize>> = vec![None; rows.first().map_or(0, Vec::len)];
+    let mut next_set = 0;
+
+    for (y, row) in rows.iter().enumerate() {
+        let last_row = y + 1 == rows.len();
+
+        for set in sets.iter_mut() {
+            if set.is_none() {
+                *set = Some(next_set);
+                next_set += 1;
+            }
+        }
+
+        for room in row {
+            rooms.open(room);
+        }
+
+        // join neighbours from different sets, always on the last row
+        for x in 1..row.len() {
+            if sets[x] != sets[x - 1] && (last_row || rng.gen_bool(0.5)) {
+                join(&mut sets, x - 1, x);
+                rooms.link(&row[x - 1], &row[x]);
+            }
+        }
+
+        if last_row {
+            break;
+        }
+
+        // the rooms of the next row every room touches
+        let downs: Vec<Vec<Point>> = row
+            .iter()
+            .map(|room| {
+                let neighbors = rooms.neighbors(room).into_iter();
+                neighbors
+                    .filter(|neighbor| neighbor.y == room.y + 1)
+                    .collect()
+            })
+            .collect();
+
+        // every set carries on to the next row at least once. A room below can only
+        // take one set, hexagons share them and half the triangles have none, so a set
+        // without a free room below joins a set next to it instead
+        let mut below = vec![None; rows[y + 1].len()];
+        let free = |x: usize, below: &[Option<usize>]| -> Vec<Point> {
+            let downs = downs[x].iter();
+            downs
+                .filter(|down| below[down.x].is_none())
+                .copied()
+                .collect()
+        };
+
+        let mut ids: Vec<usize> = sets.iter().flatten().copied().collect();
+        ids.sort();
+        ids.dedup();
+
+        for id in ids {
+            let mut members: Vec<usize> = (0..row.len())
+                .filter(|&x| sets[x] == Some(id) && !free(x, &below).is_empty())
+                .collect();
+
+            if members.is_empty() {
+                let next_to = (1..row.len()).find(|&x| {
+                    sets[x] != sets[x - 1] && (sets[x] == Some(id) || sets[x - 1] == Some(id))
+                });
+                if let Some(x) = next_to {
+                    match sets[x] == Some(id) {
+                        true => join(&mut sets, x - 1, x),
+                        false => join(&mut sets, x, x - 1),
+                    }
+                    rooms.link(&row[x - 1], &row[x]);
+                }
+                continue;
+            }
+
+            members.shuffle(rng);
+
+            let count = rng.gen_range(1..=members.len());
+
+            for &x in members.iter().take(count) {
+                // an earlier room of the set may have taken it
+                if let Some(down) = free(x, &below).choose(rng) {
+                    rooms.link(&row[x], down);
+                    below[down.x] = sets[x];
+                }
+            }
+        }
+
+        sets = below;
+    }
+}
+
+fn aldous_broder(rooms: &mut impl Carve, rng: &mut impl Rng) {
+    let mut current = rooms.random(rng);
+    rooms.open(&current);
+
+    let mut remaining = rooms.len() - 1;
+
+    while remaining > 0 {
+        let next = *rooms.neighbors(&current).choose(rng).unwrap();
+        if !rooms.is_open(&next) {
+            rooms.link(&current, &next);
+            remaining -= 1;
+        }
+        current = next;
+    }
+}
+
+// Every room carves towards an earlier one, north or west on a square grid.
+// A room without one (the first, or a triangle pointing up at the start of a row)
+// waits until the next room that touches it carves into it
+fn binary_tree(rooms: &mut impl Carve, rng: &mut impl Rng) {
+    for room in rooms.points() {
+        let index = rooms.index(&room);
+        let (options, waiting): (Vec<Point>, Vec<Point>) = rooms
+            .neighbors(&room)
+            .into_iter()
+            .filter(|neighbor| rooms.index(neighbor) < index)
+            .partition(|neighbor| rooms.is_open(neighbor));
+
+        if let Some(next) = options.choose(rng) {
+            rooms.link(&room, next);
+        }
+        for next in waiting {
+            rooms.link(&room, &next);
+        }
+    }
+
+    // a single room has nothing to carve into
+    if let Some(first) = rooms.points().first() {
+        rooms.open(first);
+    }
+}
+
+fn sidewinder(rooms: &mut impl Carve, rng: &mut impl Rng) {
+    for row in rooms.rows() {
+        let mut run_start = 0;
+
+        for x in 0..row.len() {
+            let room = row[x];
+            rooms.open(&room);
+
+            let at_east_edge = x + 1 == row.len();
+            let close_run = at_east_edge || (room.y > 0 && rng.gen_bool(0.5));
+
+            if !close_run {
+                rooms.link(&room, &row[x + 1]);
+                continue;
+            }
+            if room.y == 0 {
+                run_start = x + 1;
+                continue;
+            }
+
+            // the run goes up from one of its rooms that has a room above
+            let ups = |room: &Point| -> Vec<Point> {
+                let neighbors = rooms.neighbors(room).into_iter();
+                neighbors.filter(|neighbor| neighbor.y < room.y).collect()
+            };
+            let run: Vec<Point> = row[run_start..=x]
+                .iter()
+                .filter(|room| !ups(room).is_empty())
+                .copied()
+                .collect();
+
+            match run.choose(rng) {
+                Some(member) => {
+                    let up = *ups(member).choose(rng).unwrap();
+                    rooms.link(member, &up);
+                }
+                // none of them has, a triangle pointing up: keep the run going
+                None if !at_east_edge => {
+                    rooms.link(&room, &row[x + 1]);
+                    continue;
+                }
+                // or join the run before at the end of the row
+                None => {
+                    if let Some(previous) = run_start.checked_sub(1) {
+                        rooms.link(&row[previous], &row[run_start]);
+                    }
+                }
+            }
+            run_start = x + 1;
+        }
+    }
+}
+
+fn recursive_division(rooms: &mut Rooms, rng: &mut impl Rng) {
+    // chamber as top left room and size in rooms
+    fn divide(rooms: &mut Rooms, rng: &mut impl Rng, x: usize, y: usize, w: usize, h: usize) {
+        if w < 2 || h < 2 {
+            return;
+        }
+
+        let horizontal = match h.cmp(&w) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => rng.gen_bool(0.5),
+        };
+
+        if horizontal {
+            // wall between rows wall_y and wall_y + 1
+            let wall_y = y + rng.gen_range(0..h - 1);
+            let gap = x + rng.gen_range(0..w);
+            for i in x..x + w {
+                if i != gap {
+                    rooms.set_wall(
+                        &Point { x: i, y: wall_y },
+                        &Point {
+                            x: i,
+                            y: wall_y + 1,
+                        },
+                        true,
+                    );
+                }
+            }
+            divide(rooms, rng, x, y, w, wall_y - y + 1);
+            divide(rooms, rng, x, wall_y + 1, w, y + h - wall_y - 1);
+        } else {
+            let wall_x = x + rng.gen_range(0..w - 1);
+            let gap = y + rng.gen_range(0..h);
+            for i in y..y + h {
+                if i != gap {
+                    rooms.set_wall(
+                        &Point { x: wall_x, y: i },
+                        &Point {
+                            x: wall_x + 1,
+                            y: i,
+                        },
+                        true,
+                    );
+                }
+            }
+            divide(rooms, rng, x, y, wall_x - x + 1, h);
+            divide(rooms, rng, wall_x + 1, y, x + w - wall_x - 1, h);
+        }
+    }
+
+    // start with every room connected to its neighbours
+    for room in rooms.points() {
+        for neighbor in rooms.neighbors(&room) {
+            rooms.link(&room, &neighbor);
+        }
+    }
+
+    let (width, height) = (rooms.width, rooms.height);
+
+    divide(rooms, rng, 0, 0, width, height);
+}
+
+const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+fn is_open(grid: &[Vec<Cell>], x: isize, y: isize) -> bool {
+    0 <= y
+        && 0 <= x
+        && (y as usize) < grid.len()
+        && (x as usize) < grid[0].len()
+        && !grid[y as usize][x as usize].obsticle
+}
+
+// open cells with a single open neighbour
+fn dead_ends(grid: &[Vec<Cell>]) -> Vec<Point> {
+    grid.iter()
+        .flatten()
+        .filter(|cell| !cell.obsticle && is_dead_end(grid, &cell.point))
+        .map(|cell| cell.point)
+        .collect()
+}
+
+fn is_dead_end(grid: &[Vec<Cell>], point: &Point) -> bool {
+    let (x, y) = (point.x as isize, point.y as isize);
+    DIRECTIONS
+        .iter()
+        .filter(|(dx, dy)| is_open(grid, x + dx, y + dy))
+        .count()
+        == 1
+}
+
+// Removes about `fraction` of the dead ends by knocking out one of their walls
+// into the next open cell, dead ends joining another dead end are preferred.
+// Works on cells rather than Rooms, Prim's rooms don't always sit on even coordinates.
+pub(crate) fn braid(
+    grid: &mut [Vec<Cell>],
+    steps: &mut Vec<GenerationStep>,
+    fraction: f64,
+    rng: &mut impl Rng,
+) {
+    let mut dead_ends = dead_ends(grid);
+    dead_ends.shuffle(rng);
+
+    let count = (dead_ends.len() as f64 * fraction.clamp(0.0, 1.0)).round() as usize;
+
+    for point in dead_ends.into_iter().take(count) {
+        // an earlier wall may already have joined this one
+        if !is_dead_end(grid, &point) {
+            continue;
+        }
+
+        let (x, y) = (point.x as isize, point.y as isize);
+        let mut walls: Vec<(Point, bool)> = DIRECTIONS
+            .iter()
+            .filter(|(dx, dy)| {
+                !is_open(grid, x + dx, y + dy) && is_open(grid, x + 2 * dx, y + 2 * dy)
+            })
+            .map(|(dx, dy)| {
+                let wall = Point {
+                    x: (x + dx) as usize,
+                    y: (y + dy) as usize,
+                };
+                let beyond = Point {
+                    x: (x + 2 * dx) as usize,
+                    y: (y + 2 * dy) as usize,
+                };
+                (wall, is_dead_end(grid, &beyond))
+            })
+            .collect();
+        walls.shuffle(rng);
+
+        let wall = walls
+            .iter()
+            .find(|(_, joins_dead_end)| *joins_dead_end)
+            .or(walls.first());
+        if let Some((wall, _)) = wall {
+            set(grid, steps, wall.x, wall.y, false);
+        }
+    }
+}
+
+// parity of the room coordinates, the one where every cell is open.
+// Even for everything but Prim's, which starts on a random cell
+pub(crate) fn room_parity(grid: &[Vec<Cell>]) -> (usize, usize) {
+    [(0, 0), (1, 0), (0, 1), (1, 1)]
+        .into_iter()
+        .find(|(px, py)| {
+            grid.iter()
+                .flatten()
+                .filter(|cell| cell.point.x % 2 == *px && cell.point.y % 2 == *py)
+                .all(|cell| !cell.obsticle)
+        })
+        .unwrap_or((0, 0))
+}
+
+// knocks out about `fraction` of the walls between two rooms that are still standing,
+// every one of them adds a loop. The pillars between four walls are left alone
+pub(crate) fn add_loops(
+    grid: &mut [Vec<Cell>],
+    steps: &mut Vec<GenerationStep>,
+    fraction: f64,
+    rng: &mut impl Rng,
+) {
+    let (px, py) = room_parity(grid);
+    let mut walls: Vec<Point> = grid
+        .iter()
+        .flatten()
+        .filter(|cell| {
+            let (x, y) = (cell.point.x as isize, cell.point.y as isize);
+            let between = match (cell.point.x % 2 == px, cell.point.y % 2 == py) {
+                (false, true) => is_open(grid, x - 1, y) && is_open(grid, x + 1, y),
+                (true, false) => is_open(grid, x, y - 1) && is_open(grid, x, y + 1),
+                _ => false,
+            };
+            cell.obsticle && between
+        })
+        .map(|cell| cell.point)
+        .collect();
+    walls.shuffle(rng);
+
+    let count = (walls.len() as f64 * fraction.clamp(0.0, 1.0)).round() as usize;
+
+    for wall in walls.into_iter().take(count) {
+        set(grid, steps, wall.x, wall.y, false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashSet, VecDeque};
+
+    use crate::{
+        generation::{dead_ends, MazeAlgorithm},
+        Maze, Point,
+    };
+
+    // open cells reachable from the start
+    fn reachable(maze: &Maze) -> HashSet<Point> {
+        let mut seen = HashSet::from([maze.start]);
+        let mut queue = VecDeque::from([maze.start]);
+        while let Some(point) = queue.pop_front() {
+            for (neighbor, _) in maze.moves(&point) {
+                if seen.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        seen
+    }
+
+    fn open_cells(maze: &Maze) -> usize {
+        maze.grid
+            .iter()
+            .flatten()
+            .filter(|cell| !cell.obsticle)
+            .count()
+    }
+
+    #[test]
+    fn connected_test() {
+        for algorithm in MazeAlgorithm::ALL {
+            for (height, width) in [(21, 31), (20, 20), (9, 4)] {
+                let maze = Maze::generate_with(height, width, algorithm).unwrap();
+                assert_eq!(
+                    reachable(&maze).len(),
+                    open_cells(&maze),
+                    "{} leaves unreachable cells",
+                    algorithm.name()
+                );
+                assert!(!maze.grid[maze.destanation.y][maze.destanation.x].obsticle);
+            }
+        }
+    }
+    #[test]
+    fn perfect_test() {
+        // a spanning tree over the rooms has one passage less than rooms
+        for algorithm in MazeAlgorithm::ALL {
+            if algorithm == MazeAlgorithm::Prim {
+                continue;
+            }
+            let maze = Maze::generate_with(21, 31, algorithm).unwrap();
+            let rooms = 11 * 16;
+            assert_eq!(
+                open_cells(&maze),
+                rooms + rooms - 1,
+                "{} isn't a perfect maze",
+                algorithm.name()
+            );
+        }
+    }
+    #[test]
+    fn braid_test() {
+        for algorithm in MazeAlgorithm::ALL {
+            let perfect = Maze::builder(21, 31)
+                .algorithm(algorithm)
+                .seed(9)
+                .build()
+                .unwrap();
+            let braided = Maze::builder(21, 31)
+                .algorithm(algorithm)
+                .seed(9)
+                .braid(1.0)
+                .build()
+                .unwrap();
+            let half = Maze::builder(21, 31)
+                .algorithm(algorithm)
+                .seed(9)
+                .braid(0.5)
+                .build()
+                .unwrap();
+
+            let before = dead_ends(&perfect.grid).len();
+            assert!(before > 0);
+            assert!(
+                dead_ends(&braided.grid).is_empty(),
+                "{} keeps dead ends",
+                algorithm.name()
+            );
+            assert!(dead_ends(&half.grid).len() <= before - before / 2);
+            assert!(!dead_ends(&half.grid).is_empty());
+
+            // braiding only opens walls, start and destanation stay where they were
+            assert_eq!(reachable(&braided).len(), open_cells(&braided));
+            assert_eq!(
+                (braided.start, braided.destanation),
+                (perfect.start, perfect.destanation)
+            );
+        }
+    }
+    #[test]
+    fn loops_test() {
+        for algorithm in MazeAlgorithm::ALL {
+            if algorithm == MazeAlgorithm::Prim {
+                continue;
+            }
+            let maze = Maze::builder(21, 31)
+                .algorithm(algorithm)
+                .seed(9)
+                .loops(0.1)
+                .build()
+                .unwrap();
+            let rooms = 11 * 16;
+            // a spanning tree over the rooms opens rooms - 1 of the walls between them,
+            // every knocked out one is an extra passage
+            let walls = 10 * 16 + 11 * 15 - (rooms - 1);
+            let loops = (walls as f64 * 0.1).round() as usize;
+            assert_eq!(
+                open_cells(&maze),
+                rooms + rooms - 1 + loops,
+                "{}",
+                algorithm.name()
+            );
+        }
+    }
+}
@@ -0,0 +1,558 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Write,
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng, SeedableRng};
+
+use crate::{
+    generation::Carve,
+    movement::STRAIGHT,
+    pathfinding::{AStar, Graph},
+    topology::{Curve, Side, Topology},
+    MazeAlgorithm, MazeError, Pathfinder, Point, SearchResult,
+};
+
+// A perfect maze on any topology. Cells are always open, the walls are the sides
+// between two cells without a passage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphMaze<T: Topology> {
+    pub seed: u64,
+    pub topology: T,
+    // read through Graph::start and Graph::destanation, set with the checked setters
+    pub(crate) start: Point,
+    pub(crate) destanation: Point,
+    // the cells every cell has a passage to, by Topology::index
+    passages: Vec<Vec<Point>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GraphMazeBuilder<T: Topology> {
+    topology: T,
+    algorithm: MazeAlgorithm,
+    seed: Option<u64>,
+}
+
+impl<T: Topology + Clone> GraphMazeBuilder<T> {
+    pub fn new(topology: T) -> Self {
+        Self {
+            topology,
+            algorithm: MazeAlgorithm::default(),
+            seed: None,
+        }
+    }
+
+    // see MazeAlgorithm::any_topology
+    pub fn algorithm(mut self, algorithm: MazeAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn build(&self) -> Result<GraphMaze<T>, MazeError> {
+        if !self.algorithm.any_topology() {
+            return Err(MazeError::Unsupported(self.algorithm, self.topology.name()));
+        }
+        // rows and the longest row, the start and the destanation need two cells
+        if self.topology.len() < 2 {
+            let rows = self.topology.rows();
+            return Err(MazeError::TooSmall {
+                height: rows.len(),
+                width: rows.iter().map(Vec::len).max().unwrap_or(0),
+            });
+        }
+
+        let seed = self.seed.unwrap_or_else(|| thread_rng().gen());
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut maze = GraphMaze::closed(self.topology.clone());
+        maze.seed = seed;
+        let mut cells = Cells {
+            open: vec![false; maze.topology.len()],
+            maze: &mut maze,
+        };
+        self.algorithm.carve_cells(&mut cells, &mut rng);
+
+        maze.start = *maze.topology.cells().choose(&mut rng).unwrap();
+        // a perfect maze has a single path, the farthest cell makes it the longest
+        let distances = maze.distances(&maze.start);
+        maze.destanation = maze
+            .topology
+            .cells()
+            .into_iter()
+            .max_by_key(|cell| distances[cell])
+            .unwrap();
+        Ok(maze)
+    }
+}
+
+// the cells of a topology while an algorithm carves them
+struct Cells<'a, T: Topology> {
+    maze: &'a mut GraphMaze<T>,
+    open: Vec<bool>,
+}
+
+impl<T: Topology> Carve for Cells<'_, T> {
+    fn rows(&self) -> Vec<Vec<Point>> {
+        self.maze.topology.rows()
+    }
+
+    fn neighbors(&self, room: &Point) -> Vec<Point> {
+        self.maze.topology.neighbors(room)
+    }
+
+    fn index(&self, room: &Point) -> usize {
+        self.maze.topology.index(room)
+    }
+
+    fn is_open(&self, room: &Point) -> bool {
+        self.open[self.index(room)]
+    }
+
+    fn open(&mut self, room: &Point) {
+        let index = self.index(room);
+        self.open[index] = true;
+    }
+
+    fn link(&mut self, a: &Point, b: &Point) {
+        self.open(a);
+        self.open(b);
+        self.maze.connect(a, b);
+    }
+
+    fn len(&self) -> usize {
+        self.maze.topology.len()
+    }
+}
+
+impl<T: Topology + Clone> GraphMaze<T> {
+    pub fn builder(topology: T) -> GraphMazeBuilder<T> {
+        GraphMazeBuilder::new(topology)
+    }
+}
+
+impl<T: Topology> GraphMaze<T> {
+    // every side a wall, starts in the first cell and ends in the last one
+    pub fn closed(topology: T) -> Self {
+        let cells = topology.cells();
+        let corner = Point { x: 0, y: 0 };
+        Self {
+            seed: 0,
+            start: cells.first().copied().unwrap_or(corner),
+            destanation: cells.last().copied().unwrap_or(corner),
+            passages: vec![vec![]; topology.len()],
+            topology,
+        }
+    }
+
+    pub fn set_start(&mut self, point: &Point) -> Result<Point, MazeError> {
+        self.placeable(point, &self.destanation)?;
+        self.start = *point;
+        Ok(self.start)
+    }
+
+    pub fn set_destanation(&mut self, point: &Point) -> Result<Point, MazeError> {
+        self.placeable(point, &self.start)?;
+        self.destanation = *point;
+        Ok(self.destanation)
+    }
+
+    // a cell of the maze apart from the other end
+    fn placeable(&self, point: &Point, other: &Point) -> Result<(), MazeError> {
+        if !self.topology.contains(point) {
+            return Err(MazeError::OutOfBounds(*point));
+        }
+        if point == other {
+            return Err(MazeError::Overlaps(*point));
+        }
+        Ok(())
+    }
+
+    // the cells a cell has a passage to, in row order
+    pub fn passages(&self, point: &Point) -> &[Point] {
+        &self.passages[self.topology.index(point)]
+    }
+
+    pub fn is_linked(&self, a: &Point, b: &Point) -> bool {
+        self.passages(a).contains(b)
+    }
+
+    // both cells in the maze and next to each other
+    fn check_neighbours(&self, a: &Point, b: &Point) -> Result<(), MazeError> {
+        if let Some(point) = [a, b]
+            .into_iter()
+            .find(|point| !self.topology.contains(point))
+        {
+            return Err(MazeError::OutOfBounds(*point));
+        }
+        if !self.topology.neighbors(a).contains(b) {
+            return Err(MazeError::NotNeighbours(*a, *b));
+        }
+        Ok(())
+    }
+
+    // knocks out the wall between two neighbours
+    pub fn link(&mut self, a: &Point, b: &Point) -> Result<(), MazeError> {
+        self.check_neighbours(a, b)?;
+        self.connect(a, b);
+        Ok(())
+    }
+
+    // puts the wall between two neighbours back
+    pub fn unlink(&mut self, a: &Point, b: &Point) -> Result<(), MazeError> {
+        self.check_neighbours(a, b)?;
+        let (index_a, index_b) = (self.topology.index(a), self.topology.index(b));
+        self.passages[index_a].retain(|point| point != b);
+        self.passages[index_b].retain(|point| point != a);
+        Ok(())
+    }
+
+    // the passages stay sorted so the same maze always compares equal
+    pub(crate) fn connect(&mut self, a: &Point, b: &Point) {
+        for (from, to) in [(a, b), (b, a)] {
+            let index = self.topology.index(from);
+            let passages = &mut self.passages[index];
+            if let Err(position) =
+                passages.binary_search_by_key(&(to.y, to.x), |point| (point.y, point.x))
+            {
+                passages.insert(position, *to);
+            }
+        }
+    }
+
+    // steps from the point to every cell
+    fn distances(&self, from: &Point) -> HashMap<Point, usize> {
+        let mut distances = HashMap::from([(*from, 0)]);
+        let mut queue = VecDeque::from([*from]);
+        while let Some(point) = queue.pop_front() {
+            let distance = distances[&point];
+            for neighbor in self.passages(&point) {
+                if !distances.contains_key(neighbor) {
+                    distances.insert(*neighbor, distance + 1);
+                    queue.push_back(*neighbor);
+                }
+            }
+        }
+        distances
+    }
+
+    pub fn astar(&self) -> Option<SearchResult> {
+        AStar.search(self)
+    }
+
+    pub fn solve(&self, pathfinder: &dyn Pathfinder) -> Option<SearchResult> {
+        pathfinder.search(self)
+    }
+
+    // the walls, start and destanation and the path when it isn't empty, drawn in
+    // the shapes of the topology with cell_size pixels for a side of length 1
+    pub fn to_svg(&self, cell_size: usize, path: &[Point]) -> String {
+        const MARGIN: f64 = 0.5;
+        let (width, height) = self.topology.size();
+        let size = cell_size as f64;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" viewBox=\"{} {} {:.3} {:.3}\">\n",
+            (width + 2.0 * MARGIN) * size,
+            (height + 2.0 * MARGIN) * size,
+            -MARGIN,
+            -MARGIN,
+            width + 2.0 * MARGIN,
+            height + 2.0 * MARGIN,
+        );
+        svg.push_str(
+            "<rect x=\"-50%\" y=\"-50%\" width=\"200%\" height=\"200%\" fill=\"white\"/>\n",
+        );
+
+        for (point, colour) in [(self.start, "#2e7d32"), (self.destanation, "#c62828")] {
+            let mut outline = String::new();
+            for (i, side) in self.topology.sides(&point).iter().enumerate() {
+                if i == 0 {
+                    let _ = write!(outline, "M{:.3} {:.3} ", side.from.0, side.from.1);
+                }
+                outline.push_str(&segment(side));
+            }
+            let _ = writeln!(svg, "<path d=\"{outline}Z\" fill=\"{colour}\"/>");
+        }
+
+        if !path.is_empty() {
+            let points: Vec<String> = path
+                .iter()
+                .map(|point| {
+                    let (x, y) = self.topology.center(point);
+                    format!("{x:.3},{y:.3}")
+                })
+                .collect();
+            let _ = writeln!(
+                svg,
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"#1565c0\" stroke-width=\"0.2\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>",
+                points.join(" ")
+            );
+        }
+
+        // every wall once, from the cell that comes first
+        let mut walls = String::new();
+        for point in self.topology.cells() {
+            let index = self.topology.index(&point);
+            for side in self.topology.sides(&point) {
+                let wall = match side.neighbor {
+                    None => true,
+                    Some(neighbor) => {
+                        index < self.topology.index(&neighbor) && !self.is_linked(&point, &neighbor)
+                    }
+                };
+                if wall {
+                    let _ = write!(walls, "M{:.3} {:.3} ", side.from.0, side.from.1);
+                    walls.push_str(&segment(&side));
+                }
+            }
+        }
+        let _ = writeln!(
+            svg,
+            "<path d=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"0.1\" stroke-linecap=\"round\"/>",
+            walls.trim_end()
+        );
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+// path data drawing the side from where the pen is
+fn segment(side: &Side) -> String {
+    let (x, y) = side.to;
+    match side.curve {
+        Curve::Straight => format!("L{x:.3} {y:.3} "),
+        Curve::Arc { radius, clockwise } => {
+            format!("A{radius} {radius} 0 0 {} {x:.3} {y:.3} ", clockwise as u8)
+        }
+    }
+}
+
+impl<T: Topology> Graph for GraphMaze<T> {
+    fn start(&self) -> Point {
+        self.start
+    }
+
+    fn destanation(&self) -> Point {
+        self.destanation
+    }
+
+    fn moves(&self, point: &Point) -> Vec<(Point, usize)> {
+        self.passages(point)
+            .iter()
+            .map(|neighbor| (*neighbor, STRAIGHT))
+            .collect()
+    }
+
+    fn estimate(&self, point: &Point) -> usize {
+        STRAIGHT * self.topology.distance(point, &self.destanation)
+    }
+
+    fn path_cost(&self, path: &[Point]) -> usize {
+        STRAIGHT * path.len().saturating_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::GraphMaze,
+        movement::STRAIGHT,
+        pathfinding::{Graph, ALL},
+        topology::{Hex, Polar, Square, Topology, Triangle},
+        MazeAlgorithm, MazeError, Point,
+    };
+
+    fn assert_perfect<T: Topology + Clone>(topology: T) {
+        for algorithm in MazeAlgorithm::ALL {
+            if !algorithm.any_topology() {
+                continue;
+            }
+            let name = format!("{} on {}", algorithm.name(), topology.name());
+            let maze = GraphMaze::builder(topology.clone())
+                .algorithm(algorithm)
+                .seed(5)
+                .build()
+                .unwrap();
+
+            // a spanning tree: every cell reachable with one passage less than cells
+            let cells = topology.cells();
+            let passages: usize = cells.iter().map(|cell| maze.passages(cell).len()).sum();
+            assert_eq!(passages / 2, cells.len() - 1, "{name}");
+            assert_eq!(maze.distances(&maze.start).len(), cells.len(), "{name}");
+            for cell in cells.iter() {
+                for neighbor in maze.passages(cell) {
+                    assert!(topology.neighbors(cell).contains(neighbor), "{name}");
+                }
+            }
+
+            // there is one path only, every pathfinder but JPS finds it
+            let distance = maze.distances(&maze.start)[&maze.destanation];
+            for pathfinder in ALL {
+                if !pathfinder.supports(&maze) {
+                    assert_eq!(pathfinder.name(), "jps");
+                    assert!(maze.solve(pathfinder).is_none());
+                    continue;
+                }
+                let result = maze.solve(pathfinder).unwrap();
+                assert_eq!(result.cost, STRAIGHT * distance, "{name}");
+                assert_eq!(result.path.first(), Some(&maze.start));
+                assert_eq!(result.path.last(), Some(&maze.destanation));
+                for step in result.path.windows(2) {
+                    assert!(maze.is_linked(&step[0], &step[1]), "{name}");
+                }
+            }
+
+            let again = GraphMaze::builder(topology.clone())
+                .algorithm(algorithm)
+                .seed(5)
+                .build()
+                .unwrap();
+            assert_eq!(
+                (maze.start, maze.destanation, maze.passages),
+                (again.start, again.destanation, again.passages),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn square_test() {
+        assert_perfect(Square {
+            height: 8,
+            width: 11,
+        });
+    }
+    #[test]
+    fn hex_test() {
+        assert_perfect(Hex {
+            height: 9,
+            width: 8,
+        });
+    }
+    #[test]
+    fn triangle_test() {
+        assert_perfect(Triangle {
+            height: 7,
+            width: 12,
+        });
+    }
+    #[test]
+    fn polar_test() {
+        assert_perfect(Polar::new(7));
+        assert_perfect(Polar::new(2));
+    }
+    #[test]
+    fn division_test() {
+        let maze = GraphMaze::builder(Hex {
+            height: 5,
+            width: 5,
+        })
+        .algorithm(MazeAlgorithm::RecursiveDivision)
+        .build();
+        assert_eq!(
+            maze,
+            Err(MazeError::Unsupported(
+                MazeAlgorithm::RecursiveDivision,
+                "hex"
+            ))
+        );
+        assert_eq!(
+            GraphMaze::builder(Square {
+                height: 0,
+                width: 4
+            })
+            .build(),
+            Err(MazeError::TooSmall {
+                height: 0,
+                width: 0
+            })
+        );
+        // a single cell has no room for the destanation next to the start
+        assert_eq!(
+            GraphMaze::builder(Polar::new(1)).build(),
+            Err(MazeError::TooSmall {
+                height: 1,
+                width: 1
+            })
+        );
+    }
+    #[test]
+    fn set_start_test() {
+        let mut maze = GraphMaze::closed(Square {
+            height: 2,
+            width: 3,
+        });
+        let (start, destanation) = (Point { x: 0, y: 0 }, Point { x: 2, y: 1 });
+        assert_eq!(maze.start(), start);
+        assert_eq!(maze.destanation(), destanation);
+
+        let corner = Point { x: 2, y: 0 };
+        assert_eq!(maze.set_start(&corner), Ok(corner));
+        assert_eq!(
+            maze.set_destanation(&corner),
+            Err(MazeError::Overlaps(corner))
+        );
+        let outside = Point { x: 0, y: 2 };
+        assert_eq!(
+            maze.set_destanation(&outside),
+            Err(MazeError::OutOfBounds(outside))
+        );
+        assert_eq!(
+            maze.set_start(&outside),
+            Err(MazeError::OutOfBounds(outside))
+        );
+        assert_eq!((maze.start(), maze.destanation()), (corner, destanation));
+    }
+    #[test]
+    fn link_test() {
+        let mut maze = GraphMaze::closed(Square {
+            height: 3,
+            width: 3,
+        });
+        let (a, b) = (Point { x: 0, y: 0 }, Point { x: 1, y: 0 });
+        let (far, outside) = (Point { x: 2, y: 2 }, Point { x: 3, y: 0 });
+
+        assert_eq!(maze.link(&a, &b), Ok(()));
+        assert!(maze.is_linked(&a, &b) && maze.is_linked(&b, &a));
+        assert_eq!(maze.unlink(&b, &a), Ok(()));
+        assert!(!maze.is_linked(&a, &b));
+
+        for result in [maze.link(&a, &far), maze.unlink(&a, &far)] {
+            assert_eq!(result, Err(MazeError::NotNeighbours(a, far)));
+        }
+        for result in [maze.link(&b, &outside), maze.unlink(&outside, &b)] {
+            assert_eq!(result, Err(MazeError::OutOfBounds(outside)));
+        }
+    }
+    #[test]
+    fn svg_test() {
+        let (height, width) = (6, 9);
+        let maze = GraphMaze::builder(Square { height, width })
+            .seed(2)
+            .build()
+            .unwrap();
+        let svg = maze.to_svg(20, &maze.astar().unwrap().path);
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert!(svg.contains("<polyline"));
+
+        // the border and every side between two cells without a passage
+        let sides = height * (width - 1) + width * (height - 1);
+        let walls = 2 * (height + width) + sides - (height * width - 1);
+        let drawn = svg
+            .lines()
+            .find(|line| line.contains("stroke=\"black\""))
+            .unwrap()
+            .matches('M')
+            .count();
+        assert_eq!(drawn, walls);
+
+        let maze = GraphMaze::builder(Polar::new(5)).seed(2).build().unwrap();
+        assert!(maze.to_svg(20, &[]).contains(" A"));
+    }
+}
@@ -0,0 +1,211 @@
+// Mazes over several floors joined by stairs, see topology::Layers.
+
+use std::fmt::Display;
+
+use crate::{
+    graph::GraphMaze,
+    topology::{Layers, Topology},
+    Point,
+};
+
+pub type LayeredMaze = GraphMaze<Layers>;
+
+impl GraphMaze<Layers> {
+    // whether the stairs of a cell are open, down and up
+    pub fn stairs(&self, point: &Point) -> (bool, bool) {
+        let (down, up) = self.topology.stairs(point);
+        let open = |other: Option<Point>| other.is_some_and(|other| self.is_linked(point, &other));
+        (open(down), open(up))
+    }
+
+    // the floors next to each other in thin walls like WallMaze::to_text, with v and ^
+    // for the stairs down and up, S and E for the start and the destanation and the
+    // path marked with dots, empty without cells
+    pub fn to_text(&self, path: &[Point]) -> String {
+        let Layers {
+            floors,
+            height,
+            width,
+        } = self.topology;
+        if self.topology.is_empty() {
+            return String::new();
+        }
+        // a label and two lines for every row plus the bottom wall
+        let mut lines = vec![String::new(); 2 * height + 2];
+
+        for floor in 0..floors {
+            if floor > 0 {
+                for line in lines.iter_mut() {
+                    line.push_str("   ");
+                }
+            }
+            lines[0].push_str(&format!("{:<1$}", format!("floor {floor}"), 4 * width + 1));
+
+            // a wall unless there is a passage to the cell at (x + dx, y + dy)
+            let wall = |x: usize, y: usize, dx: isize, dy: isize| {
+                let point = self.topology.point(floor, x, y);
+                let other_x = x.checked_add_signed(dx).filter(|x| *x < width);
+                let other_y = y.checked_add_signed(dy).filter(|y| *y < height);
+                match other_x.zip(other_y) {
+                    Some((other_x, other_y)) => {
+                        let other = self.topology.point(floor, other_x, other_y);
+                        !self.is_linked(&point, &other)
+                    }
+                    None => true,
+                }
+            };
+
+            for y in 0..height {
+                let (top, middle) = (2 * y + 1, 2 * y + 2);
+                for x in 0..width {
+                    let point = self.topology.point(floor, x, y);
+                    let (down, up) = self.stairs(&point);
+                    let mark = if point == self.start {
+                        'S'
+                    } else if point == self.destanation {
+                        'E'
+                    } else if path.contains(&point) {
+                        '.'
+                    } else {
+                        ' '
+                    };
+
+                    lines[top].push('+');
+                    lines[top].push_str(if wall(x, y, 0, -1) { "---" } else { "   " });
+                    lines[middle].push(if wall(x, y, -1, 0) { '|' } else { ' ' });
+                    lines[middle].push(if down { 'v' } else { ' ' });
+                    lines[middle].push(mark);
+                    lines[middle].push(if up { '^' } else { ' ' });
+                }
+                lines[top].push('+');
+                lines[middle].push(if wall(width - 1, y, 1, 0) { '|' } else { ' ' });
+            }
+
+            let bottom = lines.last_mut().unwrap();
+            for x in 0..width {
+                bottom.push('+');
+                bottom.push_str(if wall(x, height - 1, 0, 1) {
+                    "---"
+                } else {
+                    "   "
+                });
+            }
+            bottom.push('+');
+        }
+
+        let mut text = lines.join("\n");
+        text.push('\n');
+        text
+    }
+}
+
+impl Display for GraphMaze<Layers> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_text(&[]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        levels::LayeredMaze, movement::STRAIGHT, pathfinding::ALL, topology::Layers, GraphMaze,
+        MazeAlgorithm, Point, Topology,
+    };
+
+    #[test]
+    fn generation_test() {
+        let layers = Layers {
+            floors: 3,
+            height: 6,
+            width: 7,
+        };
+        for algorithm in MazeAlgorithm::ALL {
+            if !algorithm.any_topology() {
+                continue;
+            }
+            let maze = GraphMaze::builder(layers)
+                .algorithm(algorithm)
+                .seed(8)
+                .build()
+                .unwrap();
+            let cells = layers.cells();
+
+            let passages: usize = cells.iter().map(|cell| maze.passages(cell).len()).sum();
+            assert_eq!(passages / 2, cells.len() - 1, "{}", algorithm.name());
+
+            // at least one staircase between every two floors
+            for floor in 0..2 {
+                let up = cells
+                    .iter()
+                    .filter(|cell| layers.position(cell).0 == floor)
+                    .filter(|cell| maze.stairs(cell).1)
+                    .count();
+                assert!(up > 0, "{}", algorithm.name());
+            }
+
+            // one path only, the pathfinders agree on it
+            let result = maze.astar().unwrap();
+            for pathfinder in ALL.into_iter().filter(|p| p.supports(&maze)) {
+                let other = maze.solve(pathfinder).unwrap();
+                assert_eq!(other.path, result.path, "{}", pathfinder.name());
+            }
+            assert_eq!(result.cost, STRAIGHT * (result.path.len() - 1));
+            for step in result.path.windows(2) {
+                assert!(maze.is_linked(&step[0], &step[1]));
+            }
+        }
+    }
+    #[test]
+    fn text_test() {
+        let layers = Layers {
+            floors: 2,
+            height: 2,
+            width: 2,
+        };
+        let mut maze = LayeredMaze::closed(layers);
+        maze.start = layers.point(0, 0, 0);
+        maze.destanation = layers.point(1, 0, 0);
+        let links = [
+            ((0, 0, 0), (0, 1, 0)),
+            ((0, 1, 0), (0, 1, 1)),
+            ((0, 1, 1), (1, 1, 1)),
+            ((1, 1, 1), (1, 0, 1)),
+            ((1, 0, 1), (1, 0, 0)),
+        ];
+        for ((floor_a, x_a, y_a), (floor_b, x_b, y_b)) in links {
+            let a = layers.point(floor_a, x_a, y_a);
+            let b = layers.point(floor_b, x_b, y_b);
+            maze.link(&a, &b).unwrap();
+        }
+
+        let path = maze.astar().unwrap().path;
+        assert_eq!(path.len(), 6);
+        assert_eq!(
+            maze.to_text(&path),
+            [
+                "floor 0     floor 1  ",
+                "+---+---+   +---+---+",
+                "| S   . |   | E |   |",
+                "+---+   +   +   +---+",
+                "|   | .^|   | .  v. |",
+                "+---+---+   +---+---+",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+    #[test]
+    fn empty_test() {
+        for (floors, height, width) in [(1, 0, 3), (2, 3, 0), (0, 2, 2)] {
+            let layers = Layers {
+                floors,
+                height,
+                width,
+            };
+            let maze = LayeredMaze::closed(layers);
+            assert_eq!(maze.to_text(&[]), "");
+            assert_eq!(maze.to_string(), "");
+            assert_eq!(layers.position(&Point { x: 1, y: 1 }).0, 0);
+        }
+    }
+}
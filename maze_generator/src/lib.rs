@@ -1,9 +1,34 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fmt::Display,
-};
-
-use rand::{thread_rng, Rng};
+use std::{collections::HashSet, fmt::Display};
+
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng, SeedableRng};
+
+pub use analysis::Analysis;
+pub use error::MazeError;
+pub use generation::{GenerationStep, MazeAlgorithm};
+pub use graph::GraphMaze;
+pub use movement::{Heuristic, Movement};
+pub use pathfinding::Pathfinder;
+pub use terrain::Terrain;
+pub use topology::Topology;
+
+use movement::{DIAGONAL, STRAIGHT};
+use pathfinding::{AStar, SearchStep, Trace};
+
+pub mod analysis;
+pub mod animation;
+pub mod cli;
+pub mod error;
+pub mod format;
+pub mod generation;
+pub mod graph;
+pub mod levels;
+pub mod movement;
+pub mod pathfinding;
+pub mod play;
+pub mod solvers;
+pub mod terrain;
+pub mod topology;
+pub mod walls;
 
 pub const OBSTICLE: &str = "\x1B[40m  \x1B[0m"; //BLACK
 pub const PATH: &str = "\x1B[47m  \x1B[0m"; //WHITE
@@ -12,6 +37,11 @@ pub const DESTINATION: &str = "\x1B[41m  \x1B[0m"; //RED
 
 pub const TRACE: &str = "\x1B[44m  \x1B[0m"; //BLUE
 pub const EXPANDED: &str = "\x1B[46m  \x1B[0m"; //CYAN
+pub const FRONTIER: &str = "\x1B[43m  \x1B[0m"; //YELLOW
+pub const CURRENT: &str = "\x1B[45m  \x1B[0m"; //MAGENTA
+
+pub const MUD: &str = "\x1B[48;5;137m  \x1B[0m"; //BROWN
+pub const WATER: &str = "\x1B[48;5;24m  \x1B[0m"; //DARK BLUE
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Point {
@@ -32,13 +62,22 @@ impl Point {
     pub fn manhattan_distance(&self, point: &Point) -> usize {
         self.x.abs_diff(point.x) + self.y.abs_diff(point.y)
     }
-    pub fn euclidian_distance(&self, point: &Point) -> usize {
+    // cost of the shortest eight way walk, in movement::STRAIGHT / movement::DIAGONAL units
+    pub fn octile_distance(&self, point: &Point) -> usize {
         let diff_x = self.x.abs_diff(point.x);
         let diff_y = self.y.abs_diff(point.y);
         if diff_x > diff_y {
-            return 14 * diff_y + (diff_x - diff_y);
+            return DIAGONAL * diff_y + STRAIGHT * (diff_x - diff_y);
         }
-        14 * diff_x + (diff_y - diff_x)
+        DIAGONAL * diff_x + STRAIGHT * (diff_y - diff_x)
+    }
+    pub fn euclidean_distance(&self, point: &Point) -> f64 {
+        let diff_x = self.x.abs_diff(point.x) as f64;
+        let diff_y = self.y.abs_diff(point.y) as f64;
+        diff_x.hypot(diff_y)
+    }
+    pub fn chebyshev_distance(&self, point: &Point) -> usize {
+        self.x.abs_diff(point.x).max(self.y.abs_diff(point.y))
     }
 }
 
@@ -46,179 +85,251 @@ impl Point {
 pub struct Cell {
     pub point: Point,
     pub obsticle: bool,
+    // only matters for open cells
+    pub terrain: Terrain,
 }
 
 impl Display for Cell {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Cell: point: {}, obsticle: {}",
-            self.point, self.obsticle
+            "Cell: point: {}, obsticle: {}, terrain: {}",
+            self.point,
+            self.obsticle,
+            self.terrain.name()
         )
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Maze {
+    pub seed: u64,
     pub height: usize,
     pub width: usize,
     pub start: Point,
     pub destanation: Point,
     pub grid: Vec<Vec<Cell>>,
+    pub movement: Movement,
+    // used by A*, greedy best-first and JPS
+    pub heuristic: Heuristic,
 }
 
-impl Maze {
-    pub fn generate(height: usize, width: usize) -> Self {
-        fn mark(x: usize, y: usize, grid: &mut [Vec<Cell>], frontiers: &mut Vec<Cell>) {
-            grid[y][x].obsticle = false;
-
-            if y >= 2 && !frontiers.contains(&grid[y - 2][x]) && grid[y - 2][x].obsticle {
-                let frontier = grid[y - 2][x];
-                // println!("North frontier: {}", frontier);
-                frontiers.push(frontier);
-            }
-            if y + 2 < grid.len()
-                && !frontiers.contains(&grid[y + 2][x])
-                && grid[y + 2][x].obsticle
-            {
-                let frontier = grid[y + 2][x];
-                // println!("South frontier: {}", frontier);
-                frontiers.push(frontier);
-            }
-            if x >= 2 && !frontiers.contains(&grid[y][x - 2]) && grid[y][x - 2].obsticle {
-                let frontier = grid[y][x - 2];
-                // println!("West frontier: {}", frontier);
-                frontiers.push(frontier);
-            }
-            if x + 2 < grid[0].len()
-                && !frontiers.contains(&grid[y][x + 2])
-                && grid[y][x + 2].obsticle
-            {
-                let frontier = grid[y][x + 2];
-                // println!("East frontier: {}", frontier);
-                frontiers.push(frontier);
-            }
-        }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    // from start to destanation, both included
+    pub path: Vec<Point>,
+    // in movement::STRAIGHT / movement::DIAGONAL units, times the terrain costs
+    pub cost: usize,
+    pub expanded: HashSet<Point>,
+}
 
-        let mut grid = vec![];
+#[derive(Debug, Clone)]
+pub struct MazeBuilder {
+    height: usize,
+    width: usize,
+    algorithm: MazeAlgorithm,
+    seed: Option<u64>,
+    movement: Movement,
+    heuristic: Option<Heuristic>,
+    braid: f64,
+    loops: f64,
+    terrain: f64,
+}
 
-        for y in 0..height {
-            let mut row = vec![];
-            for x in 0..width {
-                row.push(Cell {
-                    point: Point { x, y },
-                    obsticle: true,
-                })
-            }
-            grid.push(row);
+impl MazeBuilder {
+    pub fn new(height: usize, width: usize) -> Self {
+        Self {
+            height,
+            width,
+            algorithm: MazeAlgorithm::default(),
+            seed: None,
+            movement: Movement::default(),
+            heuristic: None,
+            braid: 0.0,
+            loops: 0.0,
+            terrain: 0.0,
         }
+    }
 
-        let mut rng = thread_rng();
-
-        let x = rng.gen_range(0..width);
-        let y = rng.gen_range(0..height);
+    pub fn algorithm(mut self, algorithm: MazeAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
 
-        let start_cell = grid[y][x];
+    // same seed, size and algorithm always give the same maze
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
 
-        let mut frontiers: Vec<Cell> = vec![];
+    pub fn movement(mut self, movement: Movement) -> Self {
+        self.movement = movement;
+        self
+    }
 
-        mark(x, y, &mut grid, &mut frontiers);
+    // share of the dead ends to remove, 1.0 leaves none
+    pub fn braid(mut self, fraction: f64) -> Self {
+        self.braid = fraction;
+        self
+    }
 
-        //  0
-        //3 x 1
-        //  2
+    // share of the walls between two open cells to knock out, each one adds a loop
+    pub fn loops(mut self, fraction: f64) -> Self {
+        self.loops = fraction;
+        self
+    }
 
-        while !frontiers.is_empty() {
-            let mut possible_to_crave = false;
+    // share of the open cells covered with mud and water patches
+    pub fn terrain(mut self, fraction: f64) -> Self {
+        self.terrain = fraction;
+        self
+    }
 
-            let cell_index = rng.gen_range(0..frontiers.len());
+    // defaults to the one that fits the movement, see Movement::heuristic
+    pub fn heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = Some(heuristic);
+        self
+    }
 
-            let x = frontiers[cell_index].point.x;
-            let y = frontiers[cell_index].point.y;
+    // fails on grids with fewer than two rooms
+    pub fn build(&self) -> Result<Maze, MazeError> {
+        Ok(self.build_steps()?.0)
+    }
 
-            frontiers.remove(cell_index);
+    // also returns every cell the generation changed, starting from a grid full of walls
+    pub fn build_steps(&self) -> Result<(Maze, Vec<GenerationStep>), MazeError> {
+        // rooms along one side, Prim's may start on odd coordinates and get the fewer
+        let rooms = |size: usize| match self.algorithm {
+            MazeAlgorithm::Prim if size > 1 => size / 2,
+            _ => size.div_ceil(2),
+        };
+        if rooms(self.height) * rooms(self.width) < 2 {
+            return Err(MazeError::TooSmall {
+                height: self.height,
+                width: self.width,
+            });
+        }
 
-            while !possible_to_crave {
-                let direction = rng.gen_range(0..4);
-                if direction == 0 {
-                    if y >= 2 && !grid[y - 2][x].obsticle {
-                        possible_to_crave = true;
-                        grid[y - 1][x].obsticle = false;
-                    }
-                } else if direction == 1 {
-                    if x + 2 < width && !grid[y][x + 2].obsticle {
-                        possible_to_crave = true;
-                        grid[y][x + 1].obsticle = false;
-                    }
-                } else if direction == 2 {
-                    if y + 2 < height && !grid[y + 2][x].obsticle {
-                        possible_to_crave = true;
-                        grid[y + 1][x].obsticle = false
-                    }
-                } else if x >= 2 && !grid[y][x - 2].obsticle {
-                    possible_to_crave = true;
-                    grid[y][x - 1].obsticle = false;
-                }
-            }
+        // without a seed pick a random one, so any maze can be reproduced from maze.seed
+        let seed = self.seed.unwrap_or_else(|| thread_rng().gen());
+        let mut rng = StdRng::seed_from_u64(seed);
 
-            mark(x, y, &mut grid, &mut frontiers);
+        let (mut maze, mut steps) = Maze::carve(self.height, self.width, self.algorithm, &mut rng);
+        // after the carving so the perfect maze of a seed stays the same
+        if self.braid > 0.0 {
+            generation::braid(&mut maze.grid, &mut steps, self.braid, &mut rng);
         }
-        let mut x = rng.gen_range(0..width);
-        let mut y = rng.gen_range(0..height);
-
-        let mut destanation_cell = grid[y][x];
-
-        while destanation_cell.point == start_cell.point
-            || destanation_cell.obsticle
-                // not really necessary just to make distance between start and end cells bigger
-            || destanation_cell.point.distance(&start_cell.point) < (height + width) / 2
-        {
-            x = rng.gen_range(0..width);
-            y = rng.gen_range(0..height);
-            destanation_cell = grid[y][x];
+        if self.loops > 0.0 {
+            generation::add_loops(&mut maze.grid, &mut steps, self.loops, &mut rng);
         }
-
-        Self {
-            height,
-            width,
-            grid,
-            start: start_cell.point,
-            destanation: destanation_cell.point,
+        if self.terrain > 0.0 {
+            let keep = [maze.start, maze.destanation];
+            terrain::scatter(&mut maze.grid, self.terrain, &keep, &mut rng);
         }
+        maze.seed = seed;
+        maze.movement = self.movement;
+        maze.heuristic = self.heuristic.unwrap_or(self.movement.heuristic());
+        Ok((maze, steps))
     }
+}
 
-    pub fn set_start(&mut self, x: usize, y: usize) -> Result<Point, String> {
-        let point = Point { x, y };
+impl Maze {
+    pub fn builder(height: usize, width: usize) -> MazeBuilder {
+        MazeBuilder::new(height, width)
+    }
 
-        if self.grid[y][x].obsticle {
-            return Err(format!("Can't place path on wall at {point}"));
-        }
+    pub fn generate(height: usize, width: usize) -> Result<Self, MazeError> {
+        Self::builder(height, width).build()
+    }
 
-        if self.destanation == point {
-            return Err(format!("Can't place path on destanation at {point}"));
+    pub fn generate_with(
+        height: usize,
+        width: usize,
+        algorithm: MazeAlgorithm,
+    ) -> Result<Self, MazeError> {
+        Self::builder(height, width).algorithm(algorithm).build()
+    }
+
+    pub fn generate_seeded(height: usize, width: usize, seed: u64) -> Result<Self, MazeError> {
+        Self::builder(height, width).seed(seed).build()
+    }
+
+    fn carve(
+        height: usize,
+        width: usize,
+        algorithm: MazeAlgorithm,
+        rng: &mut impl Rng,
+    ) -> (Self, Vec<GenerationStep>) {
+        let mut grid = vec![];
+
+        for y in 0..height {
+            let mut row = vec![];
+            for x in 0..width {
+                row.push(Cell {
+                    point: Point { x, y },
+                    obsticle: true,
+                    terrain: Terrain::default(),
+                })
+            }
+            grid.push(row);
         }
 
-        self.start = point;
+        let steps = algorithm.carve(&mut grid, rng);
 
-        Ok(self.start)
-    }
+        let open: Vec<Point> = grid
+            .iter()
+            .flatten()
+            .filter(|cell| !cell.obsticle)
+            .map(|cell| cell.point)
+            .collect();
 
-    pub fn set_destanation(&mut self, x: usize, y: usize) -> Result<Point, String> {
-        let point = Point { x, y };
+        let mut maze = Self {
+            seed: 0,
+            height,
+            width,
+            grid,
+            start: *open.choose(rng).unwrap(),
+            destanation: Point { x: 0, y: 0 },
+            movement: Movement::default(),
+            heuristic: Movement::default().heuristic(),
+        };
+        // the carved maze is perfect, so the cell farthest from any cell is one end of
+        // the longest path and the cell farthest from that one the other end
+        maze.start = maze.farthest(&maze.start).0;
+        maze.destanation = maze.farthest(&maze.start).0;
+        (maze, steps)
+    }
 
-        if self.grid[y][x].obsticle {
-            return Err(format!("Can't place path on wall at {point}"));
+    pub fn set_start(&mut self, x: usize, y: usize) -> Result<Point, MazeError> {
+        let point = self.placeable(x, y)?;
+        if self.destanation == point {
+            return Err(MazeError::Overlaps(point));
         }
+        self.start = point;
+        Ok(self.start)
+    }
 
+    pub fn set_destanation(&mut self, x: usize, y: usize) -> Result<Point, MazeError> {
+        let point = self.placeable(x, y)?;
         if self.start == point {
-            return Err(format!("Can't place path on start at {point}"));
+            return Err(MazeError::Overlaps(point));
         }
-
         self.destanation = point;
-
         Ok(self.destanation)
     }
 
+    // an open point inside the maze
+    fn placeable(&self, x: usize, y: usize) -> Result<Point, MazeError> {
+        let point = Point { x, y };
+        if y >= self.height || x >= self.width {
+            return Err(MazeError::OutOfBounds(point));
+        }
+        if self.grid[y][x].obsticle {
+            return Err(MazeError::OnWall(point));
+        }
+        Ok(point)
+    }
+
     pub fn draw(&self) {
         for row in self.grid.iter() {
             for cell in row.iter() {
@@ -229,135 +340,88 @@ impl Maze {
                 } else if cell.point == self.destanation {
                     print!("{DESTINATION}");
                 } else {
-                    print!("{PATH}");
+                    print!("{}", cell.terrain.colour());
                 }
             }
             println!();
         }
     }
 
-    fn neighbors(&self, point: &Point) -> Vec<&Cell> {
-        let mut points = Vec::new();
-        if 0 < point.x {
-            points.push(&self.grid[point.y][point.x - 1]);
-        }
-        if point.x < self.grid[0].len() - 1 {
-            points.push(&self.grid[point.y][point.x + 1]);
-        }
-        if 0 < point.y {
-            points.push(&self.grid[point.y - 1][point.x]);
-        }
-        if point.y < self.grid.len() - 1 {
-            points.push(&self.grid[point.y + 1][point.x]);
-        }
-        points
+    pub(crate) fn is_open(&self, x: isize, y: isize) -> bool {
+        0 <= x
+            && 0 <= y
+            && (x as usize) < self.width
+            && (y as usize) < self.height
+            && !self.grid[y as usize][x as usize].obsticle
     }
 
-    pub fn astar(&self) {
-        // the set of nodes to be evaluated
-        // the set of nodes already evaluated
-        // add start point to open
-
-        let mut open = HashSet::new();
-
-        let mut closed = HashSet::new();
-
-        open.insert(&self.start);
-
-        let mut g_scores = HashMap::new();
-
-        g_scores.insert(&self.start, 0);
-
-        let mut h_scores = HashMap::new();
+    // open points reachable in one step with the movement of the maze, and the cost
+    // of the step: STRAIGHT or DIAGONAL times the terrain cost of the point stepped on
+    pub(crate) fn moves(&self, point: &Point) -> Vec<(Point, usize)> {
+        let (x, y) = (point.x as isize, point.y as isize);
+        let mut moves = vec![];
 
-        h_scores.insert(&self.start, self.start.distance(&self.destanation));
-
-        let mut parent = HashMap::new();
-
-        // current = node in Open with the lowest f cost
-        // remove from Open
-        // add to Closed
-        // if current is the target node path has been found
-        // or else
-        // for each neighbor is not traversable or neighbor is in closed
-        //  skip to the next neighbor
-        // if new path to neighbor is shorter OR neighbor is not on Open
-        // set f cost of neighbor
-        // set parent of neighbor to current
-        // if neighbor is not in open
-        // add neighbor to open
-
-        let mut expanded = HashSet::new();
-
-        while !open.is_empty() {
-            let mut lowest_fscore = usize::MAX;
-            let mut point = &self.start;
-            let mut f_score;
-            for el in open.iter() {
-                f_score = g_scores[*el] + h_scores[*el];
-                if f_score <= lowest_fscore {
-                    lowest_fscore = f_score;
-                    point = *el;
-                }
+        for &(dx, dy) in self.movement.directions() {
+            if !self.is_open(x + dx, y + dy) {
+                continue;
             }
-            expanded.insert(point);
-            open.remove(point);
-            closed.insert(point);
-            if point == &self.destanation {
-                break;
+            let next = Point {
+                x: (x + dx) as usize,
+                y: (y + dy) as usize,
+            };
+            let terrain = self.grid[next.y][next.x].terrain.cost();
+            if dx == 0 || dy == 0 {
+                moves.push((next, STRAIGHT * terrain));
+                continue;
             }
-            for neighbor in self.neighbors(point) {
-                if closed.contains(&neighbor.point) || neighbor.obsticle {
-                    continue;
-                }
-
-                let new_cost_to_neighbor: usize =
-                    g_scores[point] + point.distance(&neighbor.point);
-
-                open.insert(&neighbor.point);
+            // the two cells the diagonal passes between
+            let passes = [self.is_open(x + dx, y), self.is_open(x, y + dy)];
+            let allowed = match self.movement {
+                Movement::EightWay {
+                    corner_cutting: true,
+                } => passes.contains(&true),
+                _ => !passes.contains(&false),
+            };
+            if allowed {
+                moves.push((next, DIAGONAL * terrain));
+            }
+        }
 
-                if g_scores.contains_key(&neighbor.point) {
-                    if new_cost_to_neighbor < g_scores[&neighbor.point] {
-                        g_scores.insert(&neighbor.point, new_cost_to_neighbor);
-                    }
-                } else {
-                    g_scores.insert(&neighbor.point, new_cost_to_neighbor);
-                }
+        moves
+    }
 
-                parent.insert(&neighbor.point, point);
+    // cost of walking the path step by step, see SearchResult::cost
+    pub fn path_cost(&self, path: &[Point]) -> usize {
+        path.windows(2)
+            .map(|step| {
+                let base = match step[0].x != step[1].x && step[0].y != step[1].y {
+                    true => DIAGONAL,
+                    false => STRAIGHT,
+                };
+                base * self.grid[step[1].y][step[1].x].terrain.cost()
+            })
+            .sum()
+    }
 
-                h_scores.insert(&neighbor.point, neighbor.point.distance(&self.destanation));
-            }
-            //
-            //for row in self.grid.iter() {
-            //    for cell in row.iter() {
-            //        if cell.obsticle {
-            //            print!("{}", OBSTICLE);
-            //        } else {
-            //            if cell.point == self.start {
-            //                print!("{}", START);
-            //            } else if cell.point == self.destanation {
-            //                print!("{}", DESTINATION);
-            //            } else if expanded.contains(&cell.point) {
-            //                print!("{}", EXPANDED);
-            //            } else {
-            //                print!("{}", PATH);
-            //            }
-            //        }
-            //    }
-            //    println!();
-            //}
-        }
-        let mut path = HashSet::new();
+    pub fn astar(&self) -> Option<SearchResult> {
+        AStar.search(self)
+    }
 
-        let mut current = &self.destanation;
+    pub fn solve(&self, pathfinder: &dyn Pathfinder) -> Option<SearchResult> {
+        pathfinder.search(self)
+    }
 
-        while current != &self.start {
-            path.insert(current);
-            current = parent[current];
+    // every step of the search in order, ending with the path when there is one
+    pub fn search_steps(&self, pathfinder: &dyn Pathfinder) -> Vec<SearchStep> {
+        let mut trace = Trace::new();
+        if let Some(result) = pathfinder.search_traced(self, &mut trace) {
+            trace.push(SearchStep::Path(result.path));
         }
+        trace.steps
+    }
 
-        println!("---");
+    pub fn draw_search(&self, result: &SearchResult) {
+        let path: HashSet<&Point> = result.path.iter().collect();
 
         for row in self.grid.iter() {
             for cell in row.iter() {
@@ -369,13 +433,288 @@ impl Maze {
                     print!("{DESTINATION}");
                 } else if path.contains(&cell.point) {
                     print!("{TRACE}");
-                } else if expanded.contains(&cell.point) {
+                } else if result.expanded.contains(&cell.point) {
                     print!("{EXPANDED}");
                 } else {
-                    print!("{PATH}");
+                    print!("{}", cell.terrain.colour());
                 }
             }
             println!();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{movement::STRAIGHT, Maze, MazeAlgorithm, MazeError, Point};
+
+    // knocks out walls between two open cells to add loops
+    pub(crate) fn braided(algorithm: MazeAlgorithm, seed: u64) -> Maze {
+        let mut maze = Maze::builder(31, 31)
+            .algorithm(algorithm)
+            .seed(seed)
+            .build()
+            .unwrap();
+        let mut rng = StdRng::seed_from_u64(seed);
+        for y in 1..maze.height - 1 {
+            for x in 1..maze.width - 1 {
+                let open = |x: usize, y: usize| !maze.grid[y][x].obsticle;
+                let between =
+                    (open(x - 1, y) && open(x + 1, y)) || (open(x, y - 1) && open(x, y + 1));
+                if maze.grid[y][x].obsticle && between && rng.gen_bool(0.3) {
+                    maze.grid[y][x].obsticle = false;
+                }
+            }
+        }
+        maze
+    }
+
+    // breadth first search ground truth, distance of every reachable point
+    pub(crate) fn bfs(maze: &Maze) -> HashMap<Point, usize> {
+        let mut distances = HashMap::from([(maze.start, 0)]);
+        let mut queue = VecDeque::from([maze.start]);
+        while let Some(point) = queue.pop_front() {
+            let distance = distances[&point];
+            for (neighbor, _) in maze.moves(&point) {
+                if let Entry::Vacant(entry) = distances.entry(neighbor) {
+                    entry.insert(distance + 1);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        distances
+    }
+
+    fn layout(maze: &Maze) -> Vec<String> {
+        maze.to_ascii().lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn seeded_test() {
+        for algorithm in MazeAlgorithm::ALL {
+            let a = Maze::builder(15, 21)
+                .algorithm(algorithm)
+                .seed(7)
+                .build()
+                .unwrap();
+            let b = Maze::builder(15, 21)
+                .algorithm(algorithm)
+                .seed(7)
+                .build()
+                .unwrap();
+            let c = Maze::builder(15, 21)
+                .algorithm(algorithm)
+                .seed(8)
+                .build()
+                .unwrap();
+            assert_eq!(a, b, "{} isn't reproducible", algorithm.name());
+            assert_ne!(a, c, "{} ignores the seed", algorithm.name());
+        }
+    }
+    #[test]
+    fn random_seed_test() {
+        let maze = Maze::generate(15, 21).unwrap();
+        assert_eq!(maze, Maze::generate_seeded(15, 21, maze.seed).unwrap());
+    }
+    #[test]
+    fn golden_prim_test() {
+        let maze = Maze::generate_seeded(9, 9, 1337).unwrap();
+        assert_eq!(
+            layout(&maze),
+            [
+                "........E",
+                "##.######",
+                ".#.#.....",
+                ".#.#.#.##",
+                ".....#...",
+                ".#######.",
+                ".......#.",
+                ".#.#####.",
+                ".#...#S..",
+            ]
+        );
+    }
+    #[test]
+    fn golden_backtracker_test() {
+        let maze = Maze::builder(9, 13)
+            .algorithm(MazeAlgorithm::RecursiveBacktracker)
+            .seed(42)
+            .build()
+            .unwrap();
+        assert_eq!(
+            layout(&maze),
+            [
+                "...........#.",
+                ".#####.###.#.",
+                ".#.....#.....",
+                ".#.#.#######.",
+                ".#.#.#.....#.",
+                ".#.#.#.###.##",
+                ".#.#.#E#.#...",
+                ".#.#####.###.",
+                "S#...........",
+            ]
+        );
+    }
+    #[test]
+    fn golden_division_test() {
+        let maze = Maze::builder(9, 13)
+            .algorithm(MazeAlgorithm::RecursiveDivision)
+            .seed(42)
+            .build()
+            .unwrap();
+        assert_eq!(
+            layout(&maze),
+            [
+                "S......#.#..E",
+                "######.#.#.##",
+                ".......#.....",
+                "####.#####.##",
+                ".......#...#.",
+                "##.#.###.###.",
+                "...#.........",
+                ".###.###.####",
+                "...#...#.....",
+            ]
+        );
+    }
+    #[test]
+    fn astar_test() {
+        for algorithm in MazeAlgorithm::ALL {
+            let maze = Maze::builder(21, 31)
+                .algorithm(algorithm)
+                .seed(3)
+                .build()
+                .unwrap();
+            let result = maze.astar().unwrap();
+
+            assert_eq!(result.path.first(), Some(&maze.start));
+            assert_eq!(result.path.last(), Some(&maze.destanation));
+            assert_eq!(result.cost, STRAIGHT * (result.path.len() - 1));
+            for step in result.path.windows(2) {
+                assert_eq!(step[0].distance(&step[1]), 1);
+                assert!(!maze.grid[step[1].y][step[1].x].obsticle);
+            }
+            assert!(result
+                .path
+                .iter()
+                .all(|point| result.expanded.contains(point)));
+        }
+    }
+    #[test]
+    fn astar_deterministic_test() {
+        // ties are broken by score and position, not by hashing order
+        let maze = Maze::builder(41, 41)
+            .algorithm(MazeAlgorithm::RecursiveDivision)
+            .seed(11)
+            .build()
+            .unwrap();
+        let result = maze.astar().unwrap();
+        for _ in 0..5 {
+            assert_eq!(maze.astar().unwrap(), result);
+        }
+    }
+    #[test]
+    fn set_start_test() {
+        let mut maze = Maze::from_ascii("S.#\n..E\n").unwrap();
+        let wall = Point { x: 2, y: 0 };
+
+        assert_eq!(maze.set_start(1, 0), Ok(Point { x: 1, y: 0 }));
+        assert_eq!(
+            maze.set_start(3, 0),
+            Err(MazeError::OutOfBounds(Point { x: 3, y: 0 }))
+        );
+        assert_eq!(
+            maze.set_start(0, 2),
+            Err(MazeError::OutOfBounds(Point { x: 0, y: 2 }))
+        );
+        assert_eq!(maze.set_start(2, 0), Err(MazeError::OnWall(wall)));
+        assert_eq!(
+            maze.set_start(2, 1),
+            Err(MazeError::Overlaps(maze.destanation))
+        );
+        assert_eq!(maze.set_destanation(2, 0), Err(MazeError::OnWall(wall)));
+        assert_eq!(
+            maze.set_destanation(1, 0),
+            Err(MazeError::Overlaps(maze.start))
+        );
+        assert_eq!(maze.set_destanation(0, 1), Ok(Point { x: 0, y: 1 }));
+        assert_eq!(
+            (maze.start, maze.destanation),
+            (Point { x: 1, y: 0 }, Point { x: 0, y: 1 })
+        );
+    }
+    #[test]
+    fn too_small_test() {
+        for algorithm in MazeAlgorithm::ALL {
+            for (height, width) in [(0, 0), (0, 5), (5, 0), (1, 1), (2, 2), (1, 2)] {
+                assert_eq!(
+                    Maze::builder(height, width).algorithm(algorithm).build(),
+                    Err(MazeError::TooSmall { height, width })
+                );
+            }
+            // two rooms and the wall between them, Prim needs two more cells
+            let width = match algorithm {
+                MazeAlgorithm::Prim => 5,
+                _ => 3,
+            };
+            for seed in 0..20 {
+                let maze = Maze::builder(1, width)
+                    .algorithm(algorithm)
+                    .seed(seed)
+                    .build()
+                    .unwrap();
+                assert_ne!(maze.start, maze.destanation, "{}", algorithm.name());
+            }
+        }
+        assert!(Maze::builder(3, 3)
+            .algorithm(MazeAlgorithm::Prim)
+            .build()
+            .is_err());
+    }
+    #[test]
+    fn unreachable_test() {
+        let mut maze = Maze::generate_seeded(15, 15, 5).unwrap();
+
+        // wall in the destanation
+        let around: Vec<Point> = maze
+            .moves(&maze.destanation)
+            .iter()
+            .map(|(point, _)| *point)
+            .collect();
+        for Point { x, y } in around {
+            maze.grid[y][x].obsticle = true;
+        }
+
+        assert_eq!(maze.astar(), None);
+    }
+    #[test]
+    fn astar_optimal_test() {
+        for algorithm in MazeAlgorithm::ALL {
+            for seed in 0..10 {
+                let maze = braided(algorithm, seed);
+                let result = maze.astar().unwrap();
+                let distances = bfs(&maze);
+
+                assert_eq!(
+                    result.cost,
+                    STRAIGHT * distances[&maze.destanation],
+                    "{} seed {seed}",
+                    algorithm.name()
+                );
+                assert_eq!(result.path.len(), distances[&maze.destanation] + 1);
+                for step in result.path.windows(2) {
+                    assert_eq!(step[0].distance(&step[1]), 1);
+                }
+                // every point on an optimal path is at its shortest distance
+                for (i, point) in result.path.iter().enumerate() {
+                    assert_eq!(distances[point], i);
+                }
+            }
+        }
+    }
+}
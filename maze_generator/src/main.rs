@@ -1,7 +1,18 @@
-use maze_generator::Maze;
+// cargo run -- --help
+use std::{env, process};
+
+use maze_generator::cli::{Options, USAGE};
 
 fn main() {
-    let maze = Maze::generate(20, 20);
-    maze.draw();
-    maze.astar();
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprint!("{error}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+    if let Err(error) = options.run() {
+        eprintln!("{error}");
+        process::exit(1);
+    }
 }
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Point;
+
+// every cost is in these units, a diagonal step is roughly 10 * sqrt(2)
+pub const STRAIGHT: usize = 10;
+pub const DIAGONAL: usize = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Movement {
+    // up, down, left and right
+    #[default]
+    FourWay,
+    // diagonal steps as well, a diagonal step needs both cells it passes to be open
+    // unless corner_cutting is set, then one of them is enough
+    EightWay {
+        corner_cutting: bool,
+    },
+}
+
+impl Movement {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FourWay => "4-way",
+            Self::EightWay {
+                corner_cutting: false,
+            } => "8-way",
+            Self::EightWay {
+                corner_cutting: true,
+            } => "8-way-corner-cutting",
+        }
+    }
+
+    // the heuristic that is exact on an empty grid, so A* stays optimal
+    pub fn heuristic(&self) -> Heuristic {
+        match self {
+            Self::FourWay => Heuristic::Manhattan,
+            Self::EightWay { .. } => Heuristic::Octile,
+        }
+    }
+
+    pub(crate) fn directions(&self) -> &'static [(isize, isize)] {
+        match self {
+            Self::FourWay => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Self::EightWay { .. } => &[
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (1, -1),
+                (-1, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+// estimate of the cost left to the destanation, in the same units as the steps.
+// Manhattan overestimates once diagonal steps are allowed, the others never do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Heuristic {
+    Manhattan,
+    Octile,
+    Euclidean,
+    Chebyshev,
+    // turns A* into Dijkstra
+    Zero,
+}
+
+impl Heuristic {
+    pub const ALL: [Self; 5] = [
+        Self::Manhattan,
+        Self::Octile,
+        Self::Euclidean,
+        Self::Chebyshev,
+        Self::Zero,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Manhattan => "manhattan",
+            Self::Octile => "octile",
+            Self::Euclidean => "euclidean",
+            Self::Chebyshev => "chebyshev",
+            Self::Zero => "zero",
+        }
+    }
+
+    pub fn estimate(&self, from: &Point, to: &Point) -> usize {
+        match self {
+            Self::Manhattan => STRAIGHT * from.manhattan_distance(to),
+            Self::Octile => from.octile_distance(to),
+            // a diagonal step costs 14, a bit less than 10 * sqrt(2), so the straight
+            // line is capped by the octile distance to never overestimate
+            Self::Euclidean => {
+                let straight_line = (STRAIGHT as f64 * from.euclidean_distance(to)) as usize;
+                straight_line.min(from.octile_distance(to))
+            }
+            Self::Chebyshev => STRAIGHT * from.chebyshev_distance(to),
+            Self::Zero => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        movement::{Heuristic, Movement, DIAGONAL, STRAIGHT},
+        Maze, Point,
+    };
+
+    // open maze with walls right of, below and up right of (1, 1)
+    fn corner(movement: Movement) -> Maze {
+        let mut maze = Maze::builder(9, 9)
+            .movement(movement)
+            .seed(0)
+            .build()
+            .unwrap();
+        for cell in maze.grid.iter_mut().flatten() {
+            cell.obsticle = false;
+        }
+        maze.grid[1][2].obsticle = true;
+        maze.grid[2][1].obsticle = true;
+        maze.grid[0][2].obsticle = true;
+        maze
+    }
+
+    #[test]
+    fn moves_test() {
+        let center = Point { x: 1, y: 1 };
+        let moves = |movement| {
+            let mut moves = corner(movement).moves(&center);
+            moves.sort_by_key(|(point, _)| (point.y, point.x));
+            moves
+        };
+
+        assert_eq!(
+            moves(Movement::FourWay),
+            [
+                (Point { x: 1, y: 0 }, STRAIGHT),
+                (Point { x: 0, y: 1 }, STRAIGHT)
+            ]
+        );
+        // up right is a wall, up left is free, down left squeezes past one wall
+        // and down right passes between two walls
+        assert_eq!(
+            moves(Movement::EightWay {
+                corner_cutting: false
+            }),
+            [
+                (Point { x: 0, y: 0 }, DIAGONAL),
+                (Point { x: 1, y: 0 }, STRAIGHT),
+                (Point { x: 0, y: 1 }, STRAIGHT)
+            ]
+        );
+        assert_eq!(
+            moves(Movement::EightWay {
+                corner_cutting: true
+            }),
+            [
+                (Point { x: 0, y: 0 }, DIAGONAL),
+                (Point { x: 1, y: 0 }, STRAIGHT),
+                (Point { x: 0, y: 1 }, STRAIGHT),
+                (Point { x: 0, y: 2 }, DIAGONAL)
+            ]
+        );
+    }
+    #[test]
+    fn estimate_test() {
+        let from = Point { x: 0, y: 0 };
+        let to = Point { x: 3, y: 4 };
+        let estimates: Vec<usize> = Heuristic::ALL
+            .iter()
+            .map(|heuristic| heuristic.estimate(&from, &to))
+            .collect();
+        assert_eq!(estimates, [70, 52, 50, 40, 0]);
+
+        // on the diagonal the straight line is longer than the 14 per step it costs
+        let to = Point { x: 100, y: 100 };
+        assert_eq!(Heuristic::Euclidean.estimate(&from, &to), 1400);
+    }
+    #[test]
+    fn admissible_test() {
+        // on an empty grid octile is the exact eight way cost, manhattan the four way one
+        for x in 0..12 {
+            for y in 0..12 {
+                let from = Point { x: 5, y: 5 };
+                let to = Point { x, y };
+                let octile = Heuristic::Octile.estimate(&from, &to);
+                let manhattan = Heuristic::Manhattan.estimate(&from, &to);
+                for heuristic in [Heuristic::Euclidean, Heuristic::Chebyshev, Heuristic::Zero] {
+                    assert!(heuristic.estimate(&from, &to) <= octile);
+                }
+                assert!(octile <= manhattan);
+            }
+        }
+    }
+}
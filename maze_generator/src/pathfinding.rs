@@ -0,0 +1,842 @@
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use crate::{
+    movement::{Movement, STRAIGHT},
+    Maze, Point, SearchResult, Terrain,
+};
+
+// What the pathfinders search: the block maze or a maze on any topology
+pub trait Graph {
+    fn start(&self) -> Point;
+    fn destanation(&self) -> Point;
+    // points reachable in one step and the cost of the step
+    fn moves(&self, point: &Point) -> Vec<(Point, usize)>;
+    // never more than the cheapest cost from the point to the destanation
+    fn estimate(&self, point: &Point) -> usize;
+    // cost of walking the path step by step
+    fn path_cost(&self, path: &[Point]) -> usize;
+
+    // the block maze, for the searches that only work on its grid
+    fn grid(&self) -> Option<&Maze> {
+        None
+    }
+}
+
+impl Graph for Maze {
+    fn start(&self) -> Point {
+        self.start
+    }
+
+    fn destanation(&self) -> Point {
+        self.destanation
+    }
+
+    fn moves(&self, point: &Point) -> Vec<(Point, usize)> {
+        Maze::moves(self, point)
+    }
+
+    fn estimate(&self, point: &Point) -> usize {
+        self.heuristic.estimate(point, &self.destanation)
+    }
+
+    fn path_cost(&self, path: &[Point]) -> usize {
+        Maze::path_cost(self, path)
+    }
+
+    fn grid(&self) -> Option<&Maze> {
+        Some(self)
+    }
+}
+
+pub trait Pathfinder {
+    fn name(&self) -> &'static str;
+    // None when the destanation can't be reached from the start or the maze isn't
+    // supported, every point put on the frontier or expanded goes to the trace as well
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult>;
+
+    // whether the pathfinder can search this kind of maze at all
+    fn supports(&self, _maze: &dyn Graph) -> bool {
+        true
+    }
+
+    fn search(&self, maze: &dyn Graph) -> Option<SearchResult> {
+        self.search_traced(maze, &mut Trace::off())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchStep {
+    // put on the open set, queue or stack
+    Frontier(Point),
+    // taken off the frontier and looked at
+    Expand(Point),
+    // the search is over, from start to destanation
+    Path(Vec<Point>),
+}
+
+// collects the steps of a search, does nothing when off so plain searches don't pay for it
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    enabled: bool,
+    pub steps: Vec<SearchStep>,
+}
+
+impl Trace {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            steps: vec![],
+        }
+    }
+
+    pub fn off() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, step: SearchStep) {
+        if self.enabled {
+            self.steps.push(step);
+        }
+    }
+}
+
+// every pathfinder, for comparing them on the same maze.
+// BFS, DFS and bidirectional BFS count steps, so with diagonal steps or terrain
+// their paths can cost more than the cheapest one
+pub const ALL: [&dyn Pathfinder; 7] = [
+    &AStar,
+    &Bfs,
+    &Dfs,
+    &Dijkstra,
+    &GreedyBestFirst,
+    &BidirectionalBfs,
+    &JumpPointSearch,
+];
+
+pub struct AStar;
+pub struct Bfs;
+pub struct Dfs;
+pub struct Dijkstra;
+// follows the heuristic only, fast but the path isn't always the shortest
+pub struct GreedyBestFirst;
+// breadth first from both ends until the searches meet
+pub struct BidirectionalBfs;
+// A* over jump points, skips the straight runs of corridors.
+// Only searches the four way block grid without terrain
+pub struct JumpPointSearch;
+
+// entry of the open set, BinaryHeap is a max heap so the order is reversed:
+// lowest f score first, ties go to the lowest h score (closest to the destanation),
+// then to the top left point so the search doesn't depend on insertion order
+#[derive(Debug, PartialEq, Eq)]
+struct Open {
+    f_score: usize,
+    h_score: usize,
+    point: Point,
+}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .f_score
+            .cmp(&self.f_score)
+            .then(other.h_score.cmp(&self.h_score))
+            .then(other.point.y.cmp(&self.point.y))
+            .then(other.point.x.cmp(&self.point.x))
+    }
+}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// walks the parents back from the end, the path goes from the start to the end
+fn backtrack(parent: &HashMap<Point, Point>, start: Point, end: Point) -> Vec<Point> {
+    let mut path = vec![end];
+
+    let mut current = end;
+
+    while current != start {
+        current = parent[&current];
+        path.push(current);
+    }
+
+    path.reverse();
+
+    path
+}
+
+// points one step away, for the searches that count steps instead of costs
+pub(crate) fn open_neighbors(maze: &dyn Graph, point: &Point) -> Vec<Point> {
+    maze.moves(point)
+        .into_iter()
+        .map(|(point, _)| point)
+        .collect()
+}
+
+// A*, Dijkstra and greedy best-first only differ in how the f score is made
+// out of the cost so far (g) and the heuristic (h)
+fn best_first(
+    maze: &dyn Graph,
+    trace: &mut Trace,
+    f_score: fn(usize, usize) -> usize,
+) -> Option<SearchResult> {
+    let (start, destanation) = (maze.start(), maze.destanation());
+
+    // points can be pushed several times, stale entries are skipped once their
+    // point is closed (lazy deletion)
+    let mut open = BinaryHeap::new();
+
+    let mut closed = HashSet::new();
+
+    let h_score = maze.estimate(&start);
+
+    open.push(Open {
+        f_score: f_score(0, h_score),
+        h_score,
+        point: start,
+    });
+    trace.push(SearchStep::Frontier(start));
+
+    let mut g_scores = HashMap::from([(start, 0)]);
+
+    let mut parent = HashMap::new();
+
+    // current = node in Open with the lowest f cost
+    // remove from Open
+    // add to Closed
+    // if current is the target node path has been found
+    // or else
+    // for each neighbor is not traversable or neighbor is in closed
+    //  skip to the next neighbor
+    // if new path to neighbor is shorter OR neighbor is not on Open
+    // set f cost of neighbor
+    // set parent of neighbor to current
+    // if neighbor is not in open
+    // add neighbor to open
+
+    while let Some(Open { point, .. }) = open.pop() {
+        if !closed.insert(point) {
+            continue;
+        }
+        trace.push(SearchStep::Expand(point));
+        if point == destanation {
+            return Some(SearchResult {
+                cost: g_scores[&point],
+                path: backtrack(&parent, start, point),
+                expanded: closed,
+            });
+        }
+        for (neighbor, step_cost) in maze.moves(&point) {
+            if closed.contains(&neighbor) {
+                continue;
+            }
+
+            let new_cost_to_neighbor: usize = g_scores[&point] + step_cost;
+
+            // only a strictly shorter path replaces the known one
+            if g_scores
+                .get(&neighbor)
+                .is_some_and(|&g_score| g_score <= new_cost_to_neighbor)
+            {
+                continue;
+            }
+
+            g_scores.insert(neighbor, new_cost_to_neighbor);
+
+            parent.insert(neighbor, point);
+
+            let h_score = maze.estimate(&neighbor);
+
+            open.push(Open {
+                f_score: f_score(new_cost_to_neighbor, h_score),
+                h_score,
+                point: neighbor,
+            });
+            trace.push(SearchStep::Frontier(neighbor));
+        }
+    }
+
+    None
+}
+
+impl Pathfinder for AStar {
+    fn name(&self) -> &'static str {
+        "a*"
+    }
+
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult> {
+        best_first(maze, trace, |g_score, h_score| g_score + h_score)
+    }
+}
+
+impl Pathfinder for Dijkstra {
+    fn name(&self) -> &'static str {
+        "dijkstra"
+    }
+
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult> {
+        best_first(maze, trace, |g_score, _| g_score)
+    }
+}
+
+impl Pathfinder for GreedyBestFirst {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult> {
+        best_first(maze, trace, |_, h_score| h_score)
+    }
+}
+
+impl Pathfinder for Bfs {
+    fn name(&self) -> &'static str {
+        "bfs"
+    }
+
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult> {
+        let (start, destanation) = (maze.start(), maze.destanation());
+        let mut parent = HashMap::new();
+        let mut seen = HashSet::from([start]);
+        let mut expanded = HashSet::new();
+        let mut queue = VecDeque::from([start]);
+        trace.push(SearchStep::Frontier(start));
+
+        while let Some(point) = queue.pop_front() {
+            expanded.insert(point);
+            trace.push(SearchStep::Expand(point));
+            if point == destanation {
+                let path = backtrack(&parent, start, point);
+                return Some(SearchResult {
+                    cost: maze.path_cost(&path),
+                    path,
+                    expanded,
+                });
+            }
+            for neighbor in open_neighbors(maze, &point) {
+                if seen.insert(neighbor) {
+                    parent.insert(neighbor, point);
+                    queue.push_back(neighbor);
+                    trace.push(SearchStep::Frontier(neighbor));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl Pathfinder for Dfs {
+    fn name(&self) -> &'static str {
+        "dfs"
+    }
+
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult> {
+        let (start, destanation) = (maze.start(), maze.destanation());
+        let mut parent = HashMap::new();
+        let mut expanded = HashSet::new();
+        let mut stack = vec![start];
+        trace.push(SearchStep::Frontier(start));
+
+        while let Some(point) = stack.pop() {
+            if !expanded.insert(point) {
+                continue;
+            }
+            trace.push(SearchStep::Expand(point));
+            if point == destanation {
+                let path = backtrack(&parent, start, point);
+                return Some(SearchResult {
+                    cost: maze.path_cost(&path),
+                    path,
+                    expanded,
+                });
+            }
+            for neighbor in open_neighbors(maze, &point) {
+                // the last push is popped first, so it is the parent that counts
+                if !expanded.contains(&neighbor) {
+                    parent.insert(neighbor, point);
+                    stack.push(neighbor);
+                    trace.push(SearchStep::Frontier(neighbor));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// one end of the bidirectional search
+struct Side {
+    parent: HashMap<Point, Point>,
+    distance: HashMap<Point, usize>,
+    frontier: Vec<Point>,
+}
+
+impl Side {
+    fn new(point: Point) -> Self {
+        Self {
+            parent: HashMap::new(),
+            distance: HashMap::from([(point, 0)]),
+            frontier: vec![point],
+        }
+    }
+}
+
+impl Pathfinder for BidirectionalBfs {
+    fn name(&self) -> &'static str {
+        "bidirectional"
+    }
+
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult> {
+        let (start, destanation) = (maze.start(), maze.destanation());
+        let mut forward = Side::new(start);
+        let mut backward = Side::new(destanation);
+        let mut expanded = HashSet::new();
+        trace.push(SearchStep::Frontier(start));
+        trace.push(SearchStep::Frontier(destanation));
+
+        // point reached from both ends
+        let mut meet = (start == destanation).then_some(start);
+
+        while meet.is_none() && !forward.frontier.is_empty() && !backward.frontier.is_empty() {
+            // grow the smaller frontier by a whole layer
+            let is_forward = forward.frontier.len() <= backward.frontier.len();
+            let (side, other) = match is_forward {
+                true => (&mut forward, &backward),
+                false => (&mut backward, &forward),
+            };
+
+            let mut best = None;
+            let mut next = vec![];
+
+            for point in std::mem::take(&mut side.frontier) {
+                expanded.insert(point);
+                trace.push(SearchStep::Expand(point));
+                let distance = side.distance[&point] + 1;
+                for neighbor in open_neighbors(maze, &point) {
+                    if side.distance.contains_key(&neighbor) {
+                        continue;
+                    }
+                    side.distance.insert(neighbor, distance);
+                    side.parent.insert(neighbor, point);
+                    next.push(neighbor);
+                    trace.push(SearchStep::Frontier(neighbor));
+
+                    // the first meeting in a layer isn't always the shortest one
+                    if let Some(rest) = other.distance.get(&neighbor) {
+                        if best.is_none_or(|(total, _)| distance + rest < total) {
+                            best = Some((distance + rest, neighbor));
+                        }
+                    }
+                }
+            }
+
+            side.frontier = next;
+            meet = best.map(|(_, point)| point);
+        }
+
+        let meet = meet?;
+
+        let mut path = backtrack(&forward.parent, start, meet);
+        let mut rest = backtrack(&backward.parent, destanation, meet);
+        rest.reverse();
+        path.extend(rest.into_iter().skip(1));
+
+        Some(SearchResult {
+            cost: maze.path_cost(&path),
+            path,
+            expanded,
+        })
+    }
+}
+
+// Jump point search on a 4-connected grid. Paths are kept in a canonical form where
+// vertical moves may turn but horizontal moves only go straight, unless a wall behind
+// forces a turn: moving right into (x, y) the cell (x, y + 1) is forced when (x - 1, y + 1)
+// is a wall, otherwise it is reached just as fast by going down first.
+// Vertical moves look left and right at every step and stop where a horizontal jump does.
+fn jump(maze: &Maze, mut x: isize, mut y: isize, dx: isize, dy: isize) -> Option<Point> {
+    loop {
+        x += dx;
+        y += dy;
+
+        if !maze.is_open(x, y) {
+            return None;
+        }
+
+        let point = Point {
+            x: x as usize,
+            y: y as usize,
+        };
+
+        if point == maze.destanation {
+            return Some(point);
+        }
+
+        if dx != 0 {
+            let forced = [-1, 1]
+                .iter()
+                .any(|&side| maze.is_open(x, y + side) && !maze.is_open(x - dx, y + side));
+            if forced {
+                return Some(point);
+            }
+        } else if jump(maze, x, y, 1, 0).is_some() || jump(maze, x, y, -1, 0).is_some() {
+            return Some(point);
+        }
+    }
+}
+
+// directions worth jumping in from a point, depending on how it was reached
+fn directions(maze: &Maze, point: Point, parent: Option<Point>) -> Vec<(isize, isize)> {
+    let Some(parent) = parent else {
+        return vec![(-1, 0), (1, 0), (0, -1), (0, 1)];
+    };
+
+    let (x, y) = (point.x as isize, point.y as isize);
+    let dx = (x - parent.x as isize).signum();
+    let dy = (y - parent.y as isize).signum();
+
+    if dx == 0 {
+        return vec![(0, dy), (-1, 0), (1, 0)];
+    }
+
+    let mut result = vec![(dx, 0)];
+    for side in [-1, 1] {
+        if maze.is_open(x, y + side) && !maze.is_open(x - dx, y + side) {
+            result.push((0, side));
+        }
+    }
+    result
+}
+
+impl Pathfinder for JumpPointSearch {
+    fn name(&self) -> &'static str {
+        "jps"
+    }
+
+    // the pruning rules only hold for four way movement on a uniform cost grid
+    fn supports(&self, maze: &dyn Graph) -> bool {
+        maze.grid().is_some_and(|grid| {
+            grid.movement == Movement::FourWay
+                && grid
+                    .grid
+                    .iter()
+                    .flatten()
+                    .all(|cell| cell.terrain == Terrain::Road)
+        })
+    }
+
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult> {
+        if !self.supports(maze) {
+            return None;
+        }
+        let maze = maze.grid()?;
+
+        let mut open = BinaryHeap::new();
+        let mut closed = HashSet::new();
+        let mut g_scores = HashMap::from([(maze.start, 0)]);
+        let mut parent = HashMap::new();
+
+        let h_score = maze.heuristic.estimate(&maze.start, &maze.destanation);
+        open.push(Open {
+            f_score: h_score,
+            h_score,
+            point: maze.start,
+        });
+        trace.push(SearchStep::Frontier(maze.start));
+
+        while let Some(Open { point, .. }) = open.pop() {
+            if !closed.insert(point) {
+                continue;
+            }
+            trace.push(SearchStep::Expand(point));
+            if point == maze.destanation {
+                break;
+            }
+            for (dx, dy) in directions(maze, point, parent.get(&point).copied()) {
+                let Some(jump_point) = jump(maze, point.x as isize, point.y as isize, dx, dy)
+                else {
+                    continue;
+                };
+                if closed.contains(&jump_point) {
+                    continue;
+                }
+
+                // jump points lie on a straight line, so the distance is the cost
+                let new_cost: usize = g_scores[&point] + STRAIGHT * point.distance(&jump_point);
+                if g_scores
+                    .get(&jump_point)
+                    .is_some_and(|&g_score| g_score <= new_cost)
+                {
+                    continue;
+                }
+
+                g_scores.insert(jump_point, new_cost);
+                parent.insert(jump_point, point);
+
+                let h_score = maze.heuristic.estimate(&jump_point, &maze.destanation);
+                open.push(Open {
+                    f_score: new_cost + h_score,
+                    h_score,
+                    point: jump_point,
+                });
+                trace.push(SearchStep::Frontier(jump_point));
+            }
+        }
+
+        if !closed.contains(&maze.destanation) {
+            return None;
+        }
+
+        // fill in the straight runs between the jump points
+        let jump_points = backtrack(&parent, maze.start, maze.destanation);
+        let mut path = vec![maze.start];
+        for pair in jump_points.windows(2) {
+            let (mut x, mut y) = (pair[0].x as isize, pair[0].y as isize);
+            let dx = (pair[1].x as isize - x).signum();
+            let dy = (pair[1].y as isize - y).signum();
+            while (x, y) != (pair[1].x as isize, pair[1].y as isize) {
+                x += dx;
+                y += dy;
+                path.push(Point {
+                    x: x as usize,
+                    y: y as usize,
+                });
+            }
+        }
+
+        Some(SearchResult {
+            cost: g_scores[&maze.destanation],
+            path,
+            expanded: closed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        movement::STRAIGHT,
+        pathfinding::{AStar, Bfs, BidirectionalBfs, Dijkstra, JumpPointSearch, Pathfinder, ALL},
+        terrain::{self, Terrain},
+        tests::{bfs, braided},
+        Heuristic, Maze, MazeAlgorithm, Movement, Point,
+    };
+
+    fn assert_valid(maze: &Maze, name: &str, path: &[Point], cost: usize) {
+        assert_eq!(path.first(), Some(&maze.start), "{name}");
+        assert_eq!(path.last(), Some(&maze.destanation), "{name}");
+        assert_eq!(maze.path_cost(path), cost, "{name}");
+        for step in path.windows(2) {
+            assert!(
+                maze.moves(&step[0])
+                    .iter()
+                    .any(|(point, _)| *point == step[1]),
+                "{name}"
+            );
+        }
+    }
+
+    // cheapest cost of every reachable point, relaxing all moves until nothing changes
+    fn costs(maze: &Maze) -> HashMap<Point, usize> {
+        let mut costs = HashMap::from([(maze.start, 0)]);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for point in maze.grid.iter().flatten().map(|cell| cell.point) {
+                let Some(&cost) = costs.get(&point) else {
+                    continue;
+                };
+                for (next, step_cost) in maze.moves(&point) {
+                    if costs
+                        .get(&next)
+                        .is_none_or(|&known| cost + step_cost < known)
+                    {
+                        costs.insert(next, cost + step_cost);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        costs
+    }
+
+    #[test]
+    fn optimal_test() {
+        let optimal: [&dyn Pathfinder; 5] =
+            [&AStar, &Bfs, &Dijkstra, &BidirectionalBfs, &JumpPointSearch];
+        for algorithm in MazeAlgorithm::ALL {
+            for seed in 0..10 {
+                let maze = braided(algorithm, seed);
+                let distance = bfs(&maze)[&maze.destanation];
+                for pathfinder in optimal {
+                    let result = pathfinder.search(&maze).unwrap();
+                    let name = format!("{} on {} seed {seed}", pathfinder.name(), algorithm.name());
+                    assert_eq!(result.cost, STRAIGHT * distance, "{name}");
+                    assert_valid(&maze, &name, &result.path, result.cost);
+                }
+            }
+        }
+    }
+    #[test]
+    fn valid_path_test() {
+        for seed in 0..10 {
+            let maze = braided(MazeAlgorithm::Kruskal, seed);
+            for pathfinder in ALL {
+                let result = pathfinder.search(&maze).unwrap();
+                assert_valid(&maze, pathfinder.name(), &result.path, result.cost);
+                assert!(result.expanded.contains(&maze.start));
+            }
+        }
+    }
+    #[test]
+    fn eight_way_test() {
+        let movements = [
+            Movement::FourWay,
+            Movement::EightWay {
+                corner_cutting: false,
+            },
+            Movement::EightWay {
+                corner_cutting: true,
+            },
+        ];
+        let optimal: [&dyn Pathfinder; 3] = [&AStar, &Dijkstra, &JumpPointSearch];
+        for algorithm in [
+            MazeAlgorithm::Prim,
+            MazeAlgorithm::Kruskal,
+            MazeAlgorithm::RecursiveDivision,
+        ] {
+            for seed in 0..5 {
+                let mut maze = braided(algorithm, seed);
+                let mut previous = usize::MAX;
+                for movement in movements {
+                    maze.movement = movement;
+                    let cheapest = costs(&maze)[&maze.destanation];
+                    // every extra kind of step can only make the path cheaper
+                    assert!(cheapest <= previous);
+                    previous = cheapest;
+
+                    for heuristic in Heuristic::ALL {
+                        // manhattan overestimates diagonal steps
+                        if heuristic == Heuristic::Manhattan && movement != Movement::FourWay {
+                            continue;
+                        }
+                        maze.heuristic = heuristic;
+                        for pathfinder in optimal.into_iter().filter(|p| p.supports(&maze)) {
+                            let result = pathfinder.search(&maze).unwrap();
+                            let name = format!(
+                                "{} with {} on {} {} seed {seed}",
+                                pathfinder.name(),
+                                heuristic.name(),
+                                movement.name(),
+                                algorithm.name()
+                            );
+                            assert_eq!(result.cost, cheapest, "{name}");
+                            assert_valid(&maze, &name, &result.path, result.cost);
+                        }
+                    }
+                    for pathfinder in ALL {
+                        // JPS only searches the four way grid
+                        let Some(result) = pathfinder.search(&maze) else {
+                            assert!(!pathfinder.supports(&maze), "{}", pathfinder.name());
+                            assert_ne!(movement, Movement::FourWay);
+                            continue;
+                        };
+                        assert_valid(&maze, pathfinder.name(), &result.path, result.cost);
+                    }
+                }
+            }
+        }
+    }
+    #[test]
+    fn weighted_test() {
+        let optimal: [&dyn Pathfinder; 3] = [&AStar, &Dijkstra, &JumpPointSearch];
+        for seed in 0..10 {
+            let mut maze = braided(MazeAlgorithm::Wilson, seed);
+            let keep = [maze.start, maze.destanation];
+            terrain::scatter(&mut maze.grid, 0.4, &keep, &mut StdRng::seed_from_u64(seed));
+            for movement in [
+                Movement::FourWay,
+                Movement::EightWay {
+                    corner_cutting: false,
+                },
+            ] {
+                maze.movement = movement;
+                maze.heuristic = movement.heuristic();
+                let cheapest = costs(&maze)[&maze.destanation];
+                for pathfinder in optimal.into_iter().filter(|p| p.supports(&maze)) {
+                    let result = pathfinder.search(&maze).unwrap();
+                    let name = format!("{} {} seed {seed}", pathfinder.name(), movement.name());
+                    assert_eq!(result.cost, cheapest, "{name}");
+                    assert_valid(&maze, &name, &result.path, result.cost);
+                }
+                // terrain leaves JPS out
+                assert!(JumpPointSearch.search(&maze).is_none());
+                for pathfinder in ALL.into_iter().filter(|p| p.supports(&maze)) {
+                    let result = pathfinder.search(&maze).unwrap();
+                    assert_valid(&maze, pathfinder.name(), &result.path, result.cost);
+                    assert!(result.cost >= cheapest);
+                }
+            }
+        }
+    }
+    #[test]
+    fn detour_test() {
+        // the middle row is water, going around on the road is longer but cheaper
+        let mut maze = Maze::generate_seeded(3, 7, 1).unwrap();
+        for cell in maze.grid.iter_mut().flatten() {
+            cell.obsticle = false;
+        }
+        for x in 1..6 {
+            maze.grid[1][x].terrain = Terrain::Water;
+        }
+        maze.set_start(0, 1).unwrap();
+        maze.set_destanation(6, 1).unwrap();
+
+        let cheapest = AStar.search(&maze).unwrap();
+        assert_eq!(cheapest.cost, 8 * STRAIGHT);
+        assert_eq!(cheapest.path.len(), 9);
+
+        let shortest = Bfs.search(&maze).unwrap();
+        assert_eq!(shortest.path.len(), 7);
+        assert_eq!(shortest.cost, 5 * 5 * STRAIGHT + STRAIGHT);
+    }
+    #[test]
+    fn open_room_test() {
+        // no walls at all: JPS only stops at the start and the destanation
+        let mut maze = Maze::generate_seeded(15, 15, 3).unwrap();
+        for cell in maze.grid.iter_mut().flatten() {
+            cell.obsticle = false;
+        }
+        maze.set_start(0, 0).unwrap();
+        maze.set_destanation(14, 14).unwrap();
+
+        let astar = AStar.search(&maze).unwrap();
+        let jps = JumpPointSearch.search(&maze).unwrap();
+        assert_eq!(jps.cost, 280);
+        assert_eq!(astar.cost, 280);
+        assert!(jps.expanded.len() < astar.expanded.len());
+    }
+    #[test]
+    fn unreachable_test() {
+        let mut maze = Maze::generate_seeded(9, 9, 1).unwrap();
+        let around: Vec<Point> = maze
+            .moves(&maze.destanation)
+            .iter()
+            .map(|(point, _)| *point)
+            .collect();
+        for Point { x, y } in around {
+            maze.grid[y][x].obsticle = true;
+        }
+        for pathfinder in ALL {
+            assert!(pathfinder.search(&maze).is_none(), "{}", pathfinder.name());
+        }
+    }
+}
@@ -0,0 +1,266 @@
+// Walking a maze by hand in the terminal: arrows or WASD move, H shows the next step
+// of the shortest path, Q or Esc gives up. The terminal is in raw mode while playing,
+// so the keys arrive one by one without Enter.
+
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use crate::{
+    pathfinding::Bfs, Maze, Pathfinder, Point, CURRENT, DESTINATION, FRONTIER, OBSTICLE, START,
+};
+
+const HOME: &str = "\x1B[H";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Move(isize, isize),
+    Hint,
+    Quit,
+}
+
+// the action of a key press, None for keys that do nothing
+pub fn action(key: &KeyEvent) -> Option<Action> {
+    if key.kind != KeyEventKind::Press {
+        return None;
+    }
+    let action = match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        KeyCode::Up | KeyCode::Char('w' | 'W') => Action::Move(0, -1),
+        KeyCode::Right | KeyCode::Char('d' | 'D') => Action::Move(1, 0),
+        KeyCode::Down | KeyCode::Char('s' | 'S') => Action::Move(0, 1),
+        KeyCode::Left | KeyCode::Char('a' | 'A') => Action::Move(-1, 0),
+        KeyCode::Char('h' | 'H' | '?') => Action::Hint,
+        KeyCode::Char('q' | 'Q') | KeyCode::Esc => Action::Quit,
+        _ => return None,
+    };
+    Some(action)
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub maze: Maze,
+    pub position: Point,
+    pub moves: usize,
+    pub hints: usize,
+    // the step shown by the last hint, until the next move
+    pub hint: Option<Point>,
+}
+
+impl Game {
+    pub fn new(maze: Maze) -> Self {
+        Self {
+            position: maze.start,
+            maze,
+            moves: 0,
+            hints: 0,
+            hint: None,
+        }
+    }
+
+    // one step if the maze allows it from the current position, walls don't count as moves
+    pub fn step(&mut self, dx: isize, dy: isize) -> bool {
+        let x = self.position.x.checked_add_signed(dx);
+        let y = self.position.y.checked_add_signed(dy);
+        let Some((x, y)) = x.zip(y) else {
+            return false;
+        };
+        let next = Point { x, y };
+        if !self
+            .maze
+            .moves(&self.position)
+            .iter()
+            .any(|(point, _)| *point == next)
+        {
+            return false;
+        }
+        self.position = next;
+        self.moves += 1;
+        self.hint = None;
+        true
+    }
+
+    // the next point on the cheapest path from the current position
+    pub fn hint(&mut self) -> Option<Point> {
+        let mut maze = self.maze.clone();
+        maze.start = self.position;
+        self.hints += 1;
+        self.hint = maze.astar().and_then(|result| result.path.get(1).copied());
+        self.hint
+    }
+
+    pub fn solved(&self) -> bool {
+        self.position == self.maze.destanation
+    }
+
+    // the fewest moves from the start to the destanation
+    pub fn optimal(&self) -> Option<usize> {
+        Bfs.search(&self.maze).map(|result| result.path.len() - 1)
+    }
+
+    pub fn summary(&self) -> String {
+        let optimal = self
+            .optimal()
+            .map_or_else(|| "none".to_string(), |moves| moves.to_string());
+        let mut summary = format!("solved in {} moves vs optimal {optimal}", self.moves);
+        if self.hints > 0 {
+            summary.push_str(&format!(" with {} hints", self.hints));
+        }
+        summary
+    }
+
+    pub fn render(&self) -> String {
+        let mut result = String::new();
+        for row in self.maze.grid.iter() {
+            for cell in row.iter() {
+                let colour = if cell.point == self.position {
+                    CURRENT
+                } else if cell.obsticle {
+                    OBSTICLE
+                } else if cell.point == self.maze.start {
+                    START
+                } else if cell.point == self.maze.destanation {
+                    DESTINATION
+                } else if self.hint == Some(cell.point) {
+                    FRONTIER
+                } else {
+                    cell.terrain.colour()
+                };
+                result.push_str(colour);
+            }
+            result.push('\n');
+        }
+        result.push_str(&format!(
+            "moves: {:<6}arrows or WASD to move, H for a hint, Q to quit\n",
+            self.moves
+        ));
+        result
+    }
+
+    fn show(&self, out: &mut impl Write) -> io::Result<()> {
+        // raw mode doesn't return the cursor to the start of the line
+        write!(out, "{HOME}{}", self.render().replace('\n', "\r\n"))?;
+        out.flush()
+    }
+
+    fn run(&mut self, out: &mut impl Write) -> io::Result<()> {
+        self.show(out)?;
+        while !self.solved() {
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            match action(&key) {
+                Some(Action::Move(dx, dy)) => {
+                    self.step(dx, dy);
+                }
+                Some(Action::Hint) => {
+                    self.hint();
+                }
+                Some(Action::Quit) => break,
+                None => continue,
+            }
+            self.show(out)?;
+        }
+        Ok(())
+    }
+
+    // plays until the destanation is reached or the player quits, see solved
+    pub fn play(&mut self) -> io::Result<()> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, EnterAlternateScreen, cursor::Hide)?;
+        let result = self.run(&mut out);
+        // give the terminal back even when the game failed
+        execute!(out, cursor::Show, LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+    use crate::{
+        play::{action, Action, Game},
+        Maze, MazeAlgorithm, Point,
+    };
+
+    #[test]
+    fn step_test() {
+        let maze = Maze::from_ascii("S.#\n#.E\n").unwrap();
+        let mut game = Game::new(maze);
+
+        assert!(!game.step(0, 1));
+        assert!(!game.step(-1, 0));
+        assert!(game.step(1, 0));
+        assert!(!game.step(1, 0));
+        assert!(game.step(0, 1));
+        assert!(!game.solved());
+        assert!(game.step(1, 0));
+        assert!(game.solved());
+        assert_eq!(game.position, Point { x: 2, y: 1 });
+        assert_eq!(game.summary(), "solved in 3 moves vs optimal 3");
+    }
+    #[test]
+    fn hint_test() {
+        for algorithm in MazeAlgorithm::ALL {
+            let maze = Maze::builder(15, 21)
+                .algorithm(algorithm)
+                .seed(9)
+                .build()
+                .unwrap();
+            let mut game = Game::new(maze);
+
+            // a detour first, the hints lead back
+            if !game.step(1, 0) && !game.step(0, 1) && !game.step(-1, 0) {
+                game.step(0, -1);
+            }
+            let detour = game.moves;
+            while !game.solved() {
+                let next = game.hint().unwrap();
+                let (dx, dy) = (
+                    next.x as isize - game.position.x as isize,
+                    next.y as isize - game.position.y as isize,
+                );
+                assert!(game.step(dx, dy));
+                assert_eq!(game.hint, None);
+            }
+
+            let optimal = game.optimal().unwrap();
+            assert_eq!(game.hints, game.moves - detour);
+            assert!(game.moves <= optimal + 2 * detour, "{}", algorithm.name());
+            assert!(game.summary().starts_with(&format!(
+                "solved in {} moves vs optimal {optimal} with",
+                game.moves
+            )));
+            assert_eq!(game.hint(), None);
+        }
+    }
+    #[test]
+    fn action_test() {
+        let press = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert_eq!(action(&press(KeyCode::Up)), Some(Action::Move(0, -1)));
+        assert_eq!(
+            action(&press(KeyCode::Char('a'))),
+            Some(Action::Move(-1, 0))
+        );
+        assert_eq!(action(&press(KeyCode::Char('S'))), Some(Action::Move(0, 1)));
+        assert_eq!(action(&press(KeyCode::Char('h'))), Some(Action::Hint));
+        assert_eq!(action(&press(KeyCode::Esc)), Some(Action::Quit));
+        assert_eq!(
+            action(&KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+        assert_eq!(action(&press(KeyCode::Char('x'))), None);
+
+        let mut release = press(KeyCode::Up);
+        release.kind = KeyEventKind::Release;
+        assert_eq!(action(&release), None);
+    }
+}
@@ -0,0 +1,444 @@
+// Solvers that walk the maze like a person inside it would, instead of searching it.
+// The walk goes to the trace as Expand steps, revisits included, so it can be replayed
+// next to a search. The path is the walk with its loops cut out, so it is the shortest
+// one in a perfect maze but not always with loops.
+//
+// Wall followers and Pledge turn left and right, so they need the block grid and
+// don't find anything on other topologies. Trémaux and dead-end filling work anywhere.
+
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
+
+use crate::{
+    pathfinding::{open_neighbors, Graph, Pathfinder, SearchStep, Trace},
+    Maze, Point, SearchResult,
+};
+
+// north, east, south and west, turning right goes one forward
+const HEADINGS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+impl Hand {
+    // turns to try in order, in quarters clockwise: the hand side, ahead, the other side, back
+    fn turns(&self) -> [usize; 4] {
+        match self {
+            Self::Left => [3, 0, 1, 2],
+            Self::Right => [1, 0, 3, 2],
+        }
+    }
+}
+
+// keeps one hand on the wall, gets lost around walls that aren't connected to the border
+pub struct WallFollower(pub Hand);
+// marks every passage it walks, goes back when it enters a known cell by a new passage
+// and never walks a passage a third time
+pub struct Tremaux;
+// fills the dead ends until only the ways between the start and the destanation are left
+pub struct DeadEndFilling;
+// heads in one direction and follows the wall with the left hand whenever it is
+// blocked, until it faces the same direction again after as many left as right turns
+pub struct Pledge;
+
+pub const ALL: [&dyn Pathfinder; 5] = [
+    &WallFollower(Hand::Left),
+    &WallFollower(Hand::Right),
+    &Tremaux,
+    &DeadEndFilling,
+    &Pledge,
+];
+
+// the walk without the parts that came back to a point already on it
+fn erase_loops(walk: &[Point]) -> Vec<Point> {
+    let mut path: Vec<Point> = vec![];
+    let mut index = HashMap::new();
+    for point in walk {
+        match index.get(point) {
+            Some(&i) => {
+                for erased in path.drain(i + 1..) {
+                    index.remove(&erased);
+                }
+            }
+            None => {
+                index.insert(*point, path.len());
+                path.push(*point);
+            }
+        }
+    }
+    path
+}
+
+fn walked(maze: &dyn Graph, walk: &[Point]) -> SearchResult {
+    let path = erase_loops(walk);
+    SearchResult {
+        cost: maze.path_cost(&path),
+        path,
+        expanded: walk.iter().copied().collect(),
+    }
+}
+
+fn ahead(maze: &Maze, point: &Point, heading: usize) -> Option<Point> {
+    let (dx, dy) = HEADINGS[heading];
+    let (x, y) = (point.x as isize + dx, point.y as isize + dy);
+    maze.is_open(x, y).then_some(Point {
+        x: x as usize,
+        y: y as usize,
+    })
+}
+
+impl Pathfinder for WallFollower {
+    fn name(&self) -> &'static str {
+        match self.0 {
+            Hand::Left => "left-hand",
+            Hand::Right => "right-hand",
+        }
+    }
+
+    fn supports(&self, maze: &dyn Graph) -> bool {
+        maze.grid().is_some()
+    }
+
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult> {
+        let graph = maze;
+        let maze = graph.grid()?;
+        let (mut point, mut heading) = (maze.start, 0);
+        let mut walk = vec![point];
+        // the same place facing the same way means it goes round in circles
+        let mut seen = HashSet::new();
+        trace.push(SearchStep::Expand(point));
+
+        while point != maze.destanation {
+            if !seen.insert((point, heading)) {
+                return None;
+            }
+            let (next, turned) = self.0.turns().into_iter().find_map(|turn| {
+                let turned = (heading + turn) % 4;
+                ahead(maze, &point, turned).map(|next| (next, turned))
+            })?;
+            (point, heading) = (next, turned);
+            walk.push(point);
+            trace.push(SearchStep::Expand(point));
+        }
+
+        Some(walked(graph, &walk))
+    }
+}
+
+// the same passage both ways
+fn passage(a: Point, b: Point) -> (Point, Point) {
+    match (a.y, a.x) <= (b.y, b.x) {
+        true => (a, b),
+        false => (b, a),
+    }
+}
+
+impl Pathfinder for Tremaux {
+    fn name(&self) -> &'static str {
+        "tremaux"
+    }
+
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult> {
+        let (start, destanation) = (maze.start(), maze.destanation());
+        let mut marks: HashMap<(Point, Point), u8> = HashMap::new();
+        let mut visited = HashSet::from([start]);
+        let (mut point, mut previous, mut known) = (start, None, false);
+        let mut walk = vec![start];
+        trace.push(SearchStep::Expand(start));
+
+        while point != destanation {
+            let mark = |a: Point, b: Point| marks.get(&passage(a, b)).copied().unwrap_or(0);
+            let neighbors = open_neighbors(maze, &point);
+
+            let back = previous
+                .filter(|previous| known && mark(*previous, point) == 1 && neighbors.len() > 1);
+            // a new passage if there is one, else the way back, never a passage walked twice
+            let next = back
+                .or_else(|| neighbors.iter().find(|n| mark(point, **n) == 0).copied())
+                .or_else(|| neighbors.iter().find(|n| mark(point, **n) == 1).copied())?;
+
+            *marks.entry(passage(point, next)).or_default() += 1;
+            known = !visited.insert(next);
+            (previous, point) = (Some(point), next);
+            walk.push(point);
+            trace.push(SearchStep::Expand(point));
+        }
+
+        Some(walked(maze, &walk))
+    }
+}
+
+impl Pathfinder for DeadEndFilling {
+    fn name(&self) -> &'static str {
+        "dead-end-filling"
+    }
+
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult> {
+        let (start, destanation) = (maze.start(), maze.destanation());
+
+        // every cell the start can reach and its ways out
+        let mut degree = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(point) = queue.pop_front() {
+            let neighbors = open_neighbors(maze, &point);
+            degree.insert(point, neighbors.len());
+            for neighbor in neighbors {
+                if let Entry::Vacant(entry) = degree.entry(neighbor) {
+                    entry.insert(0);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        if !degree.contains_key(&destanation) {
+            return None;
+        }
+
+        // fill from every dead end until the corridor behind it branches
+        let mut filled = HashSet::new();
+        let mut dead_ends: Vec<Point> = degree
+            .iter()
+            .filter(|(point, degree)| **degree <= 1 && **point != start && **point != destanation)
+            .map(|(point, _)| *point)
+            .collect();
+        dead_ends.sort_by_key(|point| (point.y, point.x));
+        while let Some(point) = dead_ends.pop() {
+            if !filled.insert(point) {
+                continue;
+            }
+            trace.push(SearchStep::Expand(point));
+            for neighbor in open_neighbors(maze, &point) {
+                if filled.contains(&neighbor) {
+                    continue;
+                }
+                let degree = degree.get_mut(&neighbor).unwrap();
+                *degree -= 1;
+                if *degree <= 1 && neighbor != start && neighbor != destanation {
+                    dead_ends.push(neighbor);
+                }
+            }
+        }
+
+        // what is left is the path, or the path with its loops
+        let mut parent = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        let mut seen = HashSet::from([start]);
+        while let Some(point) = queue.pop_front() {
+            if point == destanation {
+                break;
+            }
+            for neighbor in open_neighbors(maze, &point) {
+                if !filled.contains(&neighbor) && seen.insert(neighbor) {
+                    parent.insert(neighbor, point);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        let mut path = vec![destanation];
+        while let Some(point) = parent.get(path.last().unwrap()) {
+            path.push(*point);
+        }
+        path.reverse();
+        for point in path.iter() {
+            trace.push(SearchStep::Expand(*point));
+        }
+
+        Some(SearchResult {
+            cost: maze.path_cost(&path),
+            expanded: filled.into_iter().chain(path.iter().copied()).collect(),
+            path,
+        })
+    }
+}
+
+impl Pathfinder for Pledge {
+    fn name(&self) -> &'static str {
+        "pledge"
+    }
+
+    fn supports(&self, maze: &dyn Graph) -> bool {
+        maze.grid().is_some()
+    }
+
+    fn search_traced(&self, maze: &dyn Graph, trace: &mut Trace) -> Option<SearchResult> {
+        let graph = maze;
+        let maze = graph.grid()?;
+        let (start, destanation) = (maze.start, maze.destanation);
+
+        // the direction of the longer way to the destanation
+        let (dx, dy) = (
+            destanation.x as isize - start.x as isize,
+            destanation.y as isize - start.y as isize,
+        );
+        let preferred = match dx.abs() >= dy.abs() {
+            true if dx >= 0 => 1,
+            true => 3,
+            false if dy >= 0 => 2,
+            false => 0,
+        };
+
+        let (mut point, mut heading) = (start, preferred);
+        // left turns minus right turns since the wall was hit, 0 when free
+        let mut turns: isize = 0;
+        let mut walk = vec![point];
+        let mut seen = HashSet::new();
+        trace.push(SearchStep::Expand(point));
+
+        while point != destanation {
+            if !seen.insert((point, heading, turns)) {
+                return None;
+            }
+            let next = match turns {
+                0 => match ahead(maze, &point, heading) {
+                    Some(next) => Some(next),
+                    // hit a wall, turn right so it is on the left
+                    None => {
+                        heading = (heading + 1) % 4;
+                        turns -= 1;
+                        None
+                    }
+                },
+                _ => {
+                    let (turn, delta) = [(3, 1), (0, 0), (1, -1), (2, -2)]
+                        .into_iter()
+                        .find(|(turn, _)| ahead(maze, &point, (heading + turn) % 4).is_some())?;
+                    heading = (heading + turn) % 4;
+                    turns += delta;
+                    ahead(maze, &point, heading)
+                }
+            };
+            // the counter never settles on an unbounded number of turns in a finite maze
+            if turns.unsigned_abs() > 4 * maze.height * maze.width {
+                return None;
+            }
+            if let Some(next) = next {
+                point = next;
+                walk.push(point);
+                trace.push(SearchStep::Expand(point));
+            }
+        }
+
+        Some(walked(graph, &walk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        pathfinding::{AStar, Pathfinder, SearchStep, Trace},
+        solvers::{erase_loops, DeadEndFilling, Hand, Pledge, Tremaux, WallFollower, ALL},
+        topology::Hex,
+        GraphMaze, Maze, MazeAlgorithm, Point,
+    };
+
+    #[test]
+    fn erase_loops_test() {
+        let point = |x| Point { x, y: 0 };
+        let walk = [0, 1, 2, 3, 2, 1, 4, 5, 4, 6].map(point);
+        assert_eq!(erase_loops(&walk), [0, 1, 4, 6].map(point));
+    }
+    #[test]
+    fn perfect_test() {
+        // one path only, every solver that arrives finds it
+        for algorithm in MazeAlgorithm::ALL {
+            let maze = Maze::builder(21, 31)
+                .algorithm(algorithm)
+                .seed(2)
+                .build()
+                .unwrap();
+            let optimal = AStar.search(&maze).unwrap();
+            for solver in [
+                &WallFollower(Hand::Left) as &dyn Pathfinder,
+                &WallFollower(Hand::Right),
+                &Tremaux,
+                &DeadEndFilling,
+            ] {
+                let mut trace = Trace::new();
+                let result = solver.search_traced(&maze, &mut trace).unwrap();
+                assert_eq!(result.path, optimal.path, "{}", solver.name());
+                assert_eq!(result.cost, optimal.cost);
+
+                let walk: Vec<Point> = trace
+                    .steps
+                    .iter()
+                    .map(|step| match step {
+                        SearchStep::Expand(point) => *point,
+                        _ => panic!("{} only walks", solver.name()),
+                    })
+                    .collect();
+                assert!(walk.iter().all(|point| result.expanded.contains(point)));
+                assert_eq!(walk.last(), Some(&maze.destanation));
+            }
+            let result = Pledge.search(&maze).unwrap();
+            assert_eq!(result.path, optimal.path, "pledge on {}", algorithm.name());
+        }
+    }
+    #[test]
+    fn loops_test() {
+        // paths are loop free and end at the destanation, not always the shortest
+        for seed in 0..10 {
+            let maze = crate::tests::braided(MazeAlgorithm::RecursiveBacktracker, seed);
+            let optimal = AStar.search(&maze).unwrap();
+            for solver in ALL {
+                let Some(result) = solver.search(&maze) else {
+                    continue;
+                };
+                assert_eq!(result.path.first(), Some(&maze.start));
+                assert_eq!(result.path.last(), Some(&maze.destanation));
+                assert!(result.cost >= optimal.cost, "{}", solver.name());
+                let mut points = result.path.clone();
+                points.sort_by_key(|point| (point.y, point.x));
+                points.dedup();
+                assert_eq!(points.len(), result.path.len(), "{}", solver.name());
+            }
+            assert!(Tremaux.search(&maze).is_some());
+            assert!(DeadEndFilling.search(&maze).is_some());
+        }
+    }
+    #[test]
+    fn island_test() {
+        // the destanation is inside walls that don't touch the border, the left hand
+        // goes round the border and the right hand round the open top left corner
+        let maze = Maze::from_ascii(
+            "\
+S........
+.........
+..#####..
+..#E..#..
+..###.#..
+.........
+.........",
+        )
+        .unwrap();
+        assert!(WallFollower(Hand::Left).search(&maze).is_none());
+        assert!(WallFollower(Hand::Right).search(&maze).is_none());
+        assert!(Pledge.search(&maze).is_none());
+        assert_eq!(
+            Tremaux.search(&maze).unwrap().path.last(),
+            Some(&maze.destanation)
+        );
+        assert_eq!(DeadEndFilling.search(&maze).unwrap().path.len(), 15);
+
+        let mut closed = maze.clone();
+        closed.grid[4][5].obsticle = true;
+        for solver in ALL {
+            assert!(solver.search(&closed).is_none(), "{}", solver.name());
+        }
+    }
+    #[test]
+    fn topology_test() {
+        let maze = GraphMaze::builder(Hex {
+            height: 8,
+            width: 9,
+        })
+        .seed(5)
+        .build()
+        .unwrap();
+        let optimal = maze.astar().unwrap();
+        assert_eq!(maze.solve(&Tremaux).unwrap().path, optimal.path);
+        assert_eq!(maze.solve(&DeadEndFilling).unwrap().path, optimal.path);
+        assert!(maze.solve(&WallFollower(Hand::Left)).is_none());
+        assert!(maze.solve(&Pledge).is_none());
+    }
+}
@@ -0,0 +1,134 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{Cell, Point, MUD, PATH, WATER};
+
+// what an open cell is made of, walking into it costs the step cost times the terrain cost
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub enum Terrain {
+    #[default]
+    Road,
+    Mud,
+    Water,
+}
+
+impl Terrain {
+    pub const ALL: [Self; 3] = [Self::Road, Self::Mud, Self::Water];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Road => "road",
+            Self::Mud => "mud",
+            Self::Water => "water",
+        }
+    }
+
+    // never below 1, so the heuristics stay admissible
+    pub fn cost(&self) -> usize {
+        match self {
+            Self::Road => 1,
+            Self::Mud => 3,
+            Self::Water => 5,
+        }
+    }
+
+    pub fn colour(&self) -> &'static str {
+        match self {
+            Self::Road => PATH,
+            Self::Mud => MUD,
+            Self::Water => WATER,
+        }
+    }
+}
+
+// covers about `fraction` of the open cells with patches of mud and water,
+// the cells in `keep` (start and destanation) stay road
+pub(crate) fn scatter(grid: &mut [Vec<Cell>], fraction: f64, keep: &[Point], rng: &mut impl Rng) {
+    let open: Vec<Point> = grid
+        .iter()
+        .flatten()
+        .filter(|cell| !cell.obsticle && !keep.contains(&cell.point))
+        .map(|cell| cell.point)
+        .collect();
+
+    let target = (open.len() as f64 * fraction.clamp(0.0, 1.0)) as usize;
+    let mut covered = 0;
+
+    while covered < target {
+        let center = *open.choose(rng).unwrap();
+        let terrain = *[Terrain::Mud, Terrain::Water].choose(rng).unwrap();
+        let radius = rng.gen_range(1..=3);
+
+        for point in open.iter() {
+            if covered == target {
+                break;
+            }
+            let cell = &mut grid[point.y][point.x];
+            if point.distance(&center) <= radius && cell.terrain == Terrain::Road {
+                cell.terrain = terrain;
+                covered += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{terrain::Terrain, Maze};
+
+    fn count(maze: &Maze, terrain: Terrain) -> usize {
+        maze.grid
+            .iter()
+            .flatten()
+            .filter(|cell| !cell.obsticle && cell.terrain == terrain)
+            .count()
+    }
+
+    #[test]
+    fn scatter_test() {
+        let maze = Maze::builder(31, 31).seed(4).terrain(0.25).build().unwrap();
+        let open = maze
+            .grid
+            .iter()
+            .flatten()
+            .filter(|cell| !cell.obsticle)
+            .count();
+
+        // start and destanation are left out
+        assert_eq!(
+            count(&maze, Terrain::Mud) + count(&maze, Terrain::Water),
+            (open - 2) / 4
+        );
+        for point in [maze.start, maze.destanation] {
+            assert_eq!(maze.grid[point.y][point.x].terrain, Terrain::Road);
+        }
+        assert!(count(&maze, Terrain::Mud) > 0);
+        assert!(count(&maze, Terrain::Water) > 0);
+        assert!(maze
+            .grid
+            .iter()
+            .flatten()
+            .all(|cell| !cell.obsticle || cell.terrain == Terrain::Road));
+
+        assert_eq!(
+            maze,
+            Maze::builder(31, 31).seed(4).terrain(0.25).build().unwrap()
+        );
+    }
+    #[test]
+    fn no_terrain_test() {
+        // scattering nothing keeps the maze of the same seed
+        let maze = Maze::builder(15, 15).seed(4).build().unwrap();
+        assert_eq!(
+            maze,
+            Maze::builder(15, 15).seed(4).terrain(0.0).build().unwrap()
+        );
+        assert_eq!(
+            count(&maze, Terrain::Road),
+            maze.grid
+                .iter()
+                .flatten()
+                .filter(|cell| !cell.obsticle)
+                .count()
+        );
+    }
+}
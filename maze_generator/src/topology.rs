@@ -0,0 +1,711 @@
+use std::f64::consts::{PI, TAU};
+
+use crate::Point;
+
+// How the cells of a maze are laid out. Every cell is a Point where y is the row
+// (the ring of a polar grid) and x the position along it, the cells next to each
+// other in a row always share a side.
+pub trait Topology {
+    fn name(&self) -> &'static str;
+
+    // every cell row by row, in index order
+    fn rows(&self) -> Vec<Vec<Point>>;
+
+    // position of the cell in rows().concat()
+    fn index(&self, point: &Point) -> usize;
+
+    // the outline of the cell one side after the other, every side is shared
+    // with at most one neighbour
+    fn sides(&self, point: &Point) -> Vec<Side>;
+
+    // never more than the number of steps between two cells
+    fn distance(&self, a: &Point, b: &Point) -> usize;
+
+    // width and height of the drawing, in the units of the sides
+    fn size(&self) -> (f64, f64);
+
+    fn cells(&self) -> Vec<Point> {
+        self.rows().concat()
+    }
+
+    fn len(&self) -> usize {
+        self.rows().iter().map(Vec::len).sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn contains(&self, point: &Point) -> bool {
+        self.rows()
+            .get(point.y)
+            .is_some_and(|row| point.x < row.len())
+    }
+
+    fn neighbors(&self, point: &Point) -> Vec<Point> {
+        let mut neighbors = vec![];
+        for side in self.sides(point) {
+            if let Some(neighbor) = side.neighbor {
+                if !neighbors.contains(&neighbor) {
+                    neighbors.push(neighbor);
+                }
+            }
+        }
+        neighbors
+    }
+
+    // average of the corners, good enough to draw a path through
+    fn center(&self, point: &Point) -> (f64, f64) {
+        let sides = self.sides(point);
+        let count = sides.len() as f64;
+        let x = sides.iter().map(|side| side.from.0).sum::<f64>() / count;
+        let y = sides.iter().map(|side| side.from.1).sum::<f64>() / count;
+        (x, y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    Straight,
+    // part of a circle around the center of a polar grid
+    Arc { radius: f64, clockwise: bool },
+}
+
+// one side of a cell, from a corner to the next one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Side {
+    // the cell on the other side, None on the border
+    pub neighbor: Option<Point>,
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    pub curve: Curve,
+}
+
+impl Side {
+    fn line(neighbor: Option<Point>, from: (f64, f64), to: (f64, f64)) -> Self {
+        Self {
+            neighbor,
+            from,
+            to,
+            curve: Curve::Straight,
+        }
+    }
+}
+
+// every cell of a rectangle, row by row
+fn grid_rows(height: usize, width: usize) -> Vec<Vec<Point>> {
+    (0..height)
+        .map(|y| (0..width).map(|x| Point { x, y }).collect())
+        .collect()
+}
+
+// checked neighbour on a rectangular layout
+fn offset(point: &Point, dx: isize, dy: isize, height: usize, width: usize) -> Option<Point> {
+    let x = point.x.checked_add_signed(dx)?;
+    let y = point.y.checked_add_signed(dy)?;
+    (x < width && y < height).then_some(Point { x, y })
+}
+
+// squares of side 1, four neighbours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Square {
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Topology for Square {
+    fn name(&self) -> &'static str {
+        "square"
+    }
+
+    fn rows(&self) -> Vec<Vec<Point>> {
+        grid_rows(self.height, self.width)
+    }
+
+    fn index(&self, point: &Point) -> usize {
+        point.y * self.width + point.x
+    }
+
+    fn sides(&self, point: &Point) -> Vec<Side> {
+        let (x, y) = (point.x as f64, point.y as f64);
+        let corners = [(x, y), (x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0)];
+        // north, east, south, west
+        let directions = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+        (0..4)
+            .map(|i| {
+                let (dx, dy) = directions[i];
+                Side::line(
+                    offset(point, dx, dy, self.height, self.width),
+                    corners[i],
+                    corners[(i + 1) % 4],
+                )
+            })
+            .collect()
+    }
+
+    fn distance(&self, a: &Point, b: &Point) -> usize {
+        a.manhattan_distance(b)
+    }
+
+    fn size(&self) -> (f64, f64) {
+        (self.width as f64, self.height as f64)
+    }
+
+    fn len(&self) -> usize {
+        self.height * self.width
+    }
+}
+
+// pointy top hexagons with corners 1 away from the center, odd rows are shifted
+// half a cell to the right. Six neighbours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hex {
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Hex {
+    const WIDTH: f64 = 1.732_050_807_568_877_2; // sqrt(3)
+
+    // (x, z) of the cube coordinates, y is -x - z
+    fn cube(point: &Point) -> (isize, isize) {
+        let (x, y) = (point.x as isize, point.y as isize);
+        (x - (y - (y & 1)) / 2, y)
+    }
+}
+
+impl Topology for Hex {
+    fn name(&self) -> &'static str {
+        "hex"
+    }
+
+    fn rows(&self) -> Vec<Vec<Point>> {
+        grid_rows(self.height, self.width)
+    }
+
+    fn index(&self, point: &Point) -> usize {
+        point.y * self.width + point.x
+    }
+
+    fn sides(&self, point: &Point) -> Vec<Side> {
+        let shift = (point.y % 2) as f64 / 2.0;
+        let cx = Self::WIDTH * (point.x as f64 + shift + 0.5);
+        let cy = 1.5 * point.y as f64 + 1.0;
+        // clockwise from the top
+        let corners: Vec<(f64, f64)> = (0..6)
+            .map(|i| {
+                let angle = PI / 3.0 * i as f64 - PI / 2.0;
+                (cx + angle.cos(), cy + angle.sin())
+            })
+            .collect();
+
+        // odd rows reach one further right diagonally
+        let odd = (point.y % 2) as isize;
+        let directions = [
+            (odd, -1),     // north east
+            (1, 0),        // east
+            (odd, 1),      // south east
+            (odd - 1, 1),  // south west
+            (-1, 0),       // west
+            (odd - 1, -1), // north west
+        ];
+        (0..6)
+            .map(|i| {
+                let (dx, dy) = directions[i];
+                Side::line(
+                    offset(point, dx, dy, self.height, self.width),
+                    corners[i],
+                    corners[(i + 1) % 6],
+                )
+            })
+            .collect()
+    }
+
+    // exact on an empty grid, see cube coordinates
+    fn distance(&self, a: &Point, b: &Point) -> usize {
+        let (ax, az) = Self::cube(a);
+        let (bx, bz) = Self::cube(b);
+        let (dx, dz) = (ax - bx, az - bz);
+        ((dx.abs() + dz.abs() + (dx + dz).abs()) / 2) as usize
+    }
+
+    fn size(&self) -> (f64, f64) {
+        let shift = if self.height > 1 { 0.5 } else { 0.0 };
+        (
+            Self::WIDTH * (self.width as f64 + shift),
+            1.5 * self.height as f64 + 0.5,
+        )
+    }
+
+    fn len(&self) -> usize {
+        self.height * self.width
+    }
+}
+
+// triangles of side 1, pointing up where x + y is even and down otherwise.
+// Three neighbours: left, right and the one across the flat side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Triangle {
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Triangle {
+    const HEIGHT: f64 = 0.866_025_403_784_438_6; // sqrt(3) / 2
+}
+
+impl Topology for Triangle {
+    fn name(&self) -> &'static str {
+        "triangle"
+    }
+
+    fn rows(&self) -> Vec<Vec<Point>> {
+        grid_rows(self.height, self.width)
+    }
+
+    fn index(&self, point: &Point) -> usize {
+        point.y * self.width + point.x
+    }
+
+    fn sides(&self, point: &Point) -> Vec<Side> {
+        let left = point.x as f64 / 2.0;
+        let top = point.y as f64 * Self::HEIGHT;
+        let bottom = top + Self::HEIGHT;
+        let (width, height) = (self.width, self.height);
+        let west = offset(point, -1, 0, height, width);
+        let east = offset(point, 1, 0, height, width);
+
+        if (point.x + point.y).is_multiple_of(2) {
+            let apex = (left + 0.5, top);
+            let (bottom_left, bottom_right) = ((left, bottom), (left + 1.0, bottom));
+            vec![
+                Side::line(west, bottom_left, apex),
+                Side::line(east, apex, bottom_right),
+                Side::line(
+                    offset(point, 0, 1, height, width),
+                    bottom_right,
+                    bottom_left,
+                ),
+            ]
+        } else {
+            let apex = (left + 0.5, bottom);
+            let (top_left, top_right) = ((left, top), (left + 1.0, top));
+            vec![
+                Side::line(offset(point, 0, -1, height, width), top_left, top_right),
+                Side::line(east, top_right, apex),
+                Side::line(west, apex, top_left),
+            ]
+        }
+    }
+
+    // every step changes either the row or the column by one
+    fn distance(&self, a: &Point, b: &Point) -> usize {
+        a.manhattan_distance(b)
+    }
+
+    fn size(&self) -> (f64, f64) {
+        (
+            (self.width as f64 + 1.0) / 2.0,
+            self.height as f64 * Self::HEIGHT,
+        )
+    }
+
+    fn len(&self) -> usize {
+        self.height * self.width
+    }
+}
+
+// Rings of width 1 around a single center cell. The rings are split in more cells
+// as they grow, so the cells stay about as wide as they are deep: a cell has one
+// neighbour inwards and one or more outwards, y is the ring and x goes clockwise
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polar {
+    // cells in every ring, starting with the center
+    counts: Vec<usize>,
+}
+
+impl Polar {
+    pub fn new(rings: usize) -> Self {
+        let mut counts: Vec<usize> = vec![];
+        for ring in 0..rings {
+            let count = match counts.last() {
+                None => 1,
+                Some(&previous) => {
+                    let width = TAU * ring as f64 / previous as f64;
+                    previous * (width.round() as usize).max(1)
+                }
+            };
+            counts.push(count);
+        }
+        Self { counts }
+    }
+
+    pub fn rings(&self) -> usize {
+        self.counts.len()
+    }
+
+    // cells in a ring
+    pub fn count(&self, ring: usize) -> usize {
+        self.counts[ring]
+    }
+
+    fn center_point(&self) -> (f64, f64) {
+        let radius = self.rings() as f64;
+        (radius, radius)
+    }
+
+    fn at(&self, radius: f64, angle: f64) -> (f64, f64) {
+        let (cx, cy) = self.center_point();
+        (cx + radius * angle.cos(), cy + radius * angle.sin())
+    }
+
+    fn arc(&self, neighbor: Option<Point>, radius: f64, from: f64, to: f64) -> Side {
+        Side {
+            neighbor,
+            from: self.at(radius, from),
+            to: self.at(radius, to),
+            curve: Curve::Arc {
+                radius,
+                clockwise: to > from,
+            },
+        }
+    }
+
+    // outer side of a cell, split where the next ring splits it
+    fn outer(&self, point: &Point, start: f64, end: f64) -> Vec<Side> {
+        let radius = point.y as f64 + 1.0;
+        let Some(&outer) = self.counts.get(point.y + 1) else {
+            // a full circle is drawn as three arcs, one arc can't start where it ends
+            let parts = if self.count(point.y) == 1 { 3 } else { 1 };
+            let step = (end - start) / parts as f64;
+            return (0..parts)
+                .map(|i| {
+                    let from = start + step * i as f64;
+                    self.arc(None, radius, from, from + step)
+                })
+                .collect();
+        };
+        let ratio = outer / self.count(point.y);
+        let step = (end - start) / ratio as f64;
+        (0..ratio)
+            .map(|i| {
+                let neighbor = Point {
+                    x: point.x * ratio + i,
+                    y: point.y + 1,
+                };
+                let from = start + step * i as f64;
+                self.arc(Some(neighbor), radius, from, from + step)
+            })
+            .collect()
+    }
+}
+
+impl Topology for Polar {
+    fn name(&self) -> &'static str {
+        "polar"
+    }
+
+    fn rows(&self) -> Vec<Vec<Point>> {
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(y, &count)| (0..count).map(|x| Point { x, y }).collect())
+            .collect()
+    }
+
+    fn index(&self, point: &Point) -> usize {
+        self.counts[..point.y].iter().sum::<usize>() + point.x
+    }
+
+    fn sides(&self, point: &Point) -> Vec<Side> {
+        let count = self.count(point.y);
+        let step = TAU / count as f64;
+        let (start, end) = (step * point.x as f64, step * (point.x + 1) as f64);
+
+        if point.y == 0 {
+            return self.outer(point, start, end);
+        }
+
+        let (inner, outer) = (point.y as f64, point.y as f64 + 1.0);
+        let inward = Point {
+            x: point.x * self.count(point.y - 1) / count,
+            y: point.y - 1,
+        };
+        let clockwise = Point {
+            x: (point.x + 1) % count,
+            y: point.y,
+        };
+        let counter_clockwise = Point {
+            x: (point.x + count - 1) % count,
+            y: point.y,
+        };
+
+        let mut sides = vec![Side::line(
+            Some(counter_clockwise),
+            self.at(inner, start),
+            self.at(outer, start),
+        )];
+        sides.extend(self.outer(point, start, end));
+        sides.push(Side::line(
+            Some(clockwise),
+            self.at(outer, end),
+            self.at(inner, end),
+        ));
+        sides.push(self.arc(Some(inward), inner, end, start));
+        sides
+    }
+
+    // every step changes the ring by one at most
+    fn distance(&self, a: &Point, b: &Point) -> usize {
+        a.y.abs_diff(b.y)
+    }
+
+    fn size(&self) -> (f64, f64) {
+        let diameter = 2.0 * self.rings() as f64;
+        (diameter, diameter)
+    }
+
+    fn len(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+// Floors of squares stacked on top of each other, with stairs from every cell to the
+// ones right above and below it. The rows snake through the floors so every row touches
+// the next: floor 0 is counted from its top row, floor 1 from its bottom row and so on,
+// use point and position rather than working out y
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layers {
+    pub floors: usize,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Layers {
+    // the cell in column x and row y of a floor
+    pub fn point(&self, floor: usize, x: usize, y: usize) -> Point {
+        let row = match floor.is_multiple_of(2) {
+            true => y,
+            false => self.height - 1 - y,
+        };
+        Point {
+            x,
+            y: floor * self.height + row,
+        }
+    }
+
+    // floor, column and row of a cell, everything is on floor 0 without rows
+    pub fn position(&self, point: &Point) -> (usize, usize, usize) {
+        let floor = point.y.checked_div(self.height).unwrap_or(0);
+        let row = point.y.checked_rem(self.height).unwrap_or(0);
+        let y = match floor.is_multiple_of(2) {
+            true => row,
+            false => self.height - 1 - row,
+        };
+        (floor, point.x, y)
+    }
+
+    // the cells the stairs of a cell lead to, one floor down and one floor up
+    pub fn stairs(&self, point: &Point) -> (Option<Point>, Option<Point>) {
+        let (floor, x, y) = self.position(point);
+        let down = floor.checked_sub(1).map(|floor| self.point(floor, x, y));
+        let up = (floor + 1 < self.floors).then(|| self.point(floor + 1, x, y));
+        (down, up)
+    }
+}
+
+impl Topology for Layers {
+    fn name(&self) -> &'static str {
+        "layers"
+    }
+
+    fn rows(&self) -> Vec<Vec<Point>> {
+        grid_rows(self.floors * self.height, self.width)
+    }
+
+    fn index(&self, point: &Point) -> usize {
+        point.y * self.width + point.x
+    }
+
+    // the squares of a floor, the floors are drawn next to each other
+    // with a column between them. Stairs aren't sides, see neighbors
+    fn sides(&self, point: &Point) -> Vec<Side> {
+        let (floor, column, row) = self.position(point);
+        let on_floor = Point { x: column, y: row };
+        let left = (floor * (self.width + 1)) as f64;
+
+        Square {
+            height: self.height,
+            width: self.width,
+        }
+        .sides(&on_floor)
+        .into_iter()
+        .map(|side| Side {
+            neighbor: side
+                .neighbor
+                .map(|neighbor| self.point(floor, neighbor.x, neighbor.y)),
+            from: (side.from.0 + left, side.from.1),
+            to: (side.to.0 + left, side.to.1),
+            curve: side.curve,
+        })
+        .collect()
+    }
+
+    fn neighbors(&self, point: &Point) -> Vec<Point> {
+        let mut neighbors: Vec<Point> = self
+            .sides(point)
+            .iter()
+            .filter_map(|side| side.neighbor)
+            .collect();
+        let (down, up) = self.stairs(point);
+        neighbors.extend(down);
+        neighbors.extend(up);
+        neighbors
+    }
+
+    fn distance(&self, a: &Point, b: &Point) -> usize {
+        let (floor_a, x_a, y_a) = self.position(a);
+        let (floor_b, x_b, y_b) = self.position(b);
+        floor_a.abs_diff(floor_b) + x_a.abs_diff(x_b) + y_a.abs_diff(y_b)
+    }
+
+    fn size(&self) -> (f64, f64) {
+        let width = self.floors * (self.width + 1) - 1;
+        (width as f64, self.height as f64)
+    }
+
+    fn len(&self) -> usize {
+        self.floors * self.height * self.width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashSet, VecDeque};
+
+    use crate::{
+        topology::{Hex, Layers, Polar, Square, Topology, Triangle},
+        Point,
+    };
+
+    fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+        (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9
+    }
+
+    // neighbours go both ways, share the same side and every cell can be reached
+    fn assert_consistent(topology: &impl Topology) {
+        let cells = topology.cells();
+        assert_eq!(cells.len(), topology.len(), "{}", topology.name());
+        for (index, cell) in cells.iter().enumerate() {
+            assert_eq!(topology.index(cell), index, "{}", topology.name());
+            assert!(topology.contains(cell));
+
+            for side in topology.sides(cell) {
+                let Some(neighbor) = side.neighbor else {
+                    continue;
+                };
+                let name = format!("{} {cell} {neighbor}", topology.name());
+                assert!(topology.contains(&neighbor), "{name}");
+                assert!(topology.neighbors(&neighbor).contains(cell), "{name}");
+                assert!(
+                    topology.sides(&neighbor).iter().any(|other| {
+                        other.neighbor == Some(*cell)
+                            && close(other.from, side.to)
+                            && close(other.to, side.from)
+                    }),
+                    "{name}"
+                );
+                assert!(topology.distance(cell, &neighbor) <= 1, "{name}");
+            }
+        }
+
+        let mut seen = HashSet::from([cells[0]]);
+        let mut queue = VecDeque::from([cells[0]]);
+        while let Some(cell) = queue.pop_front() {
+            for neighbor in topology.neighbors(&cell) {
+                if seen.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        assert_eq!(seen.len(), cells.len(), "{}", topology.name());
+    }
+
+    #[test]
+    fn square_test() {
+        let square = Square {
+            height: 4,
+            width: 6,
+        };
+        assert_consistent(&square);
+        assert_eq!(square.neighbors(&Point { x: 0, y: 0 }).len(), 2);
+        assert_eq!(square.neighbors(&Point { x: 2, y: 2 }).len(), 4);
+    }
+    #[test]
+    fn hex_test() {
+        let hex = Hex {
+            height: 5,
+            width: 6,
+        };
+        assert_consistent(&hex);
+        assert_eq!(hex.neighbors(&Point { x: 2, y: 2 }).len(), 6);
+        assert_eq!(hex.neighbors(&Point { x: 2, y: 1 }).len(), 6);
+        assert_eq!(
+            hex.distance(&Point { x: 0, y: 0 }, &Point { x: 5, y: 4 }),
+            7
+        );
+    }
+    #[test]
+    fn triangle_test() {
+        let triangle = Triangle {
+            height: 4,
+            width: 7,
+        };
+        assert_consistent(&triangle);
+        assert_eq!(triangle.sides(&Point { x: 3, y: 1 }).len(), 3);
+        assert_eq!(triangle.neighbors(&Point { x: 3, y: 1 }).len(), 3);
+    }
+    #[test]
+    fn polar_test() {
+        let polar = Polar::new(6);
+        assert_eq!(
+            (0..6).map(|ring| polar.count(ring)).collect::<Vec<_>>(),
+            [1, 6, 12, 24, 24, 24]
+        );
+        assert_consistent(&polar);
+        // the center touches the whole first ring
+        assert_eq!(polar.neighbors(&Point { x: 0, y: 0 }).len(), 6);
+        // a cell of a ring that gets split has two neighbours outwards
+        assert_eq!(polar.neighbors(&Point { x: 3, y: 2 }).len(), 5);
+        assert_consistent(&Polar::new(1));
+    }
+    #[test]
+    fn layers_test() {
+        let layers = Layers {
+            floors: 3,
+            height: 4,
+            width: 5,
+        };
+        assert_eq!(layers.len(), 60);
+        for (index, cell) in layers.cells().iter().enumerate() {
+            assert_eq!(layers.index(cell), index);
+            let (floor, x, y) = layers.position(cell);
+            assert_eq!(layers.point(floor, x, y), *cell);
+            for neighbor in layers.neighbors(cell) {
+                assert!(layers.neighbors(&neighbor).contains(cell));
+                assert_eq!(layers.distance(cell, &neighbor), 1);
+            }
+        }
+
+        // the rows snake, the last row of a floor lies under the first row of the next
+        let corner = layers.point(0, 2, 3);
+        assert_eq!(corner, Point { x: 2, y: 3 });
+        assert_eq!(layers.stairs(&corner), (None, Some(Point { x: 2, y: 4 })));
+        let middle = layers.point(1, 2, 1);
+        assert_eq!(layers.neighbors(&middle).len(), 6);
+        assert_eq!(
+            layers.distance(&layers.point(0, 0, 0), &layers.point(2, 4, 3)),
+            9
+        );
+    }
+}
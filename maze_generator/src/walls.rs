@@ -0,0 +1,348 @@
+// Square mazes with a wall on every side of a cell. The block grid of Maze spends
+// whole cells on walls, so a 20 x 20 Maze has 10 x 10 rooms, a 20 x 20 WallMaze has 400.
+
+use std::fmt::Display;
+
+use crate::{
+    generation::room_parity,
+    graph::GraphMaze,
+    topology::{Square, Topology},
+    Cell, Maze, MazeError, Movement, Point, Terrain,
+};
+
+pub type WallMaze = GraphMaze<Square>;
+
+// the sides of a cell with a wall, the border always has one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Walls {
+    pub north: bool,
+    pub east: bool,
+    pub south: bool,
+    pub west: bool,
+}
+
+impl GraphMaze<Square> {
+    pub fn walls(&self, point: &Point) -> Walls {
+        let wall = |dx: isize, dy: isize| {
+            let x = point.x.checked_add_signed(dx);
+            let y = point.y.checked_add_signed(dy);
+            match x.zip(y) {
+                Some((x, y)) => !self.is_linked(point, &Point { x, y }),
+                None => true,
+            }
+        };
+        Walls {
+            north: wall(0, -1),
+            east: wall(1, 0),
+            south: wall(0, 1),
+            west: wall(-1, 0),
+        }
+    }
+
+    // The rooms of the block grid become cells and the open cells between two rooms
+    // the passages. Terrain and open cells between four rooms are lost, a start or a
+    // destanation between two rooms moves to the room above or left of it
+    pub fn from_maze(maze: &Maze) -> Self {
+        let (px, py) = room_parity(&maze.grid);
+        let square = Square {
+            height: maze.height.saturating_sub(py).div_ceil(2).max(1),
+            width: maze.width.saturating_sub(px).div_ceil(2).max(1),
+        };
+
+        let open =
+            |x: usize, y: usize| x < maze.width && y < maze.height && !maze.grid[y][x].obsticle;
+        let room = |point: &Point| Point {
+            x: (point.x.saturating_sub(px) / 2).min(square.width - 1),
+            y: (point.y.saturating_sub(py) / 2).min(square.height - 1),
+        };
+
+        let mut walls = Self::closed(square);
+        walls.seed = maze.seed;
+        walls.start = room(&maze.start);
+        walls.destanation = room(&maze.destanation);
+
+        for cell in square.cells() {
+            let (x, y) = (2 * cell.x + px, 2 * cell.y + py);
+            if !open(x, y) {
+                continue;
+            }
+            if open(x + 1, y) && open(x + 2, y) {
+                walls.connect(
+                    &cell,
+                    &Point {
+                        x: cell.x + 1,
+                        y: cell.y,
+                    },
+                );
+            }
+            if open(x, y + 1) && open(x, y + 2) {
+                walls.connect(
+                    &cell,
+                    &Point {
+                        x: cell.x,
+                        y: cell.y + 1,
+                    },
+                );
+            }
+        }
+        walls
+    }
+
+    // the block grid with the rooms on even coordinates and no border,
+    // 2 * height - 1 by 2 * width - 1 cells, like Maze::builder it needs two rooms
+    // and the start apart from the destanation
+    pub fn to_maze(&self) -> Result<Maze, MazeError> {
+        if self.topology.len() < 2 {
+            return Err(MazeError::TooSmall {
+                height: self.topology.height,
+                width: self.topology.width,
+            });
+        }
+        if self.start == self.destanation {
+            return Err(MazeError::Overlaps(self.start));
+        }
+        let height = 2 * self.topology.height - 1;
+        let width = 2 * self.topology.width - 1;
+
+        let mut grid: Vec<Vec<Cell>> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| Cell {
+                        point: Point { x, y },
+                        obsticle: true,
+                        terrain: Terrain::default(),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        for cell in self.topology.cells() {
+            grid[2 * cell.y][2 * cell.x].obsticle = false;
+            // the block between two rooms sits at the sum of their coordinates
+            for neighbor in self.passages(&cell) {
+                grid[cell.y + neighbor.y][cell.x + neighbor.x].obsticle = false;
+            }
+        }
+
+        let block = |point: &Point| Point {
+            x: 2 * point.x,
+            y: 2 * point.y,
+        };
+        Ok(Maze {
+            seed: self.seed,
+            height,
+            width,
+            start: block(&self.start),
+            destanation: block(&self.destanation),
+            grid,
+            movement: Movement::default(),
+            heuristic: Movement::default().heuristic(),
+        })
+    }
+
+    // thin walls out of +, - and |, with S and E for the start and the destanation
+    // and the path marked with dots, empty without cells
+    pub fn to_text(&self, path: &[Point]) -> String {
+        let Square { height, width } = self.topology;
+        let mut text = String::new();
+        if self.topology.is_empty() {
+            return text;
+        }
+
+        for y in 0..height {
+            let mut top = String::new();
+            let mut middle = String::new();
+            for x in 0..width {
+                let point = Point { x, y };
+                let walls = self.walls(&point);
+                top.push('+');
+                top.push_str(if walls.north { "---" } else { "   " });
+                middle.push(if walls.west { '|' } else { ' ' });
+                middle.push_str(if point == self.start {
+                    " S "
+                } else if point == self.destanation {
+                    " E "
+                } else if path.contains(&point) {
+                    " . "
+                } else {
+                    "   "
+                });
+                if x + 1 == width {
+                    top.push('+');
+                    middle.push(if walls.east { '|' } else { ' ' });
+                }
+            }
+            text.push_str(&top);
+            text.push('\n');
+            text.push_str(&middle);
+            text.push('\n');
+        }
+
+        for x in 0..width {
+            let walls = self.walls(&Point { x, y: height - 1 });
+            text.push('+');
+            text.push_str(if walls.south { "---" } else { "   " });
+        }
+        text.push_str("+\n");
+        text
+    }
+}
+
+impl Display for GraphMaze<Square> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_text(&[]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        topology::{Square, Topology},
+        walls::WallMaze,
+        GraphMaze, Maze, MazeAlgorithm, MazeError, Point,
+    };
+
+    fn walls(maze: &Maze) -> Vec<Vec<bool>> {
+        maze.grid
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.obsticle).collect())
+            .collect()
+    }
+
+    #[test]
+    fn from_maze_test() {
+        // every block algorithm gives a perfect maze with exactly the rooms of the grid
+        for algorithm in MazeAlgorithm::ALL {
+            for (height, width) in [(21, 31), (20, 20)] {
+                let maze = Maze::builder(height, width)
+                    .algorithm(algorithm)
+                    .seed(6)
+                    .build()
+                    .unwrap();
+                let wall_maze = WallMaze::from_maze(&maze);
+                let rooms = wall_maze.topology.len();
+                let passages: usize = wall_maze
+                    .topology
+                    .cells()
+                    .iter()
+                    .map(|cell| wall_maze.passages(cell).len())
+                    .sum();
+                assert_eq!(passages / 2, rooms - 1, "{}", algorithm.name());
+                assert_eq!(wall_maze.seed, 6);
+
+                // Prim's rooms may sit on odd coordinates, the others on even ones
+                if algorithm == MazeAlgorithm::Prim {
+                    continue;
+                }
+                assert_eq!(rooms, height.div_ceil(2) * width.div_ceil(2));
+
+                // odd sizes have no wall only row or column, so they come back the same
+                if height % 2 == 1 {
+                    let back = wall_maze.to_maze().unwrap();
+                    assert_eq!(walls(&back), walls(&maze), "{}", algorithm.name());
+                    assert!(!back.grid[back.start.y][back.start.x].obsticle);
+                }
+            }
+        }
+    }
+    #[test]
+    fn to_maze_test() {
+        for algorithm in MazeAlgorithm::ALL {
+            if !algorithm.any_topology() {
+                continue;
+            }
+            let maze = GraphMaze::builder(Square {
+                height: 12,
+                width: 17,
+            })
+            .algorithm(algorithm)
+            .seed(6)
+            .build()
+            .unwrap();
+
+            let block = maze.to_maze().unwrap();
+            assert_eq!((block.height, block.width), (23, 33));
+            assert_eq!(WallMaze::from_maze(&block), maze, "{}", algorithm.name());
+            assert_eq!(
+                block.astar().unwrap().cost,
+                2 * maze.astar().unwrap().cost,
+                "{}",
+                algorithm.name()
+            );
+        }
+    }
+    #[test]
+    fn walls_test() {
+        let mut maze = WallMaze::closed(Square {
+            height: 2,
+            width: 3,
+        });
+        maze.link(&Point { x: 0, y: 0 }, &Point { x: 1, y: 0 })
+            .unwrap();
+        maze.link(&Point { x: 1, y: 0 }, &Point { x: 1, y: 1 })
+            .unwrap();
+        maze.link(&Point { x: 1, y: 1 }, &Point { x: 2, y: 1 })
+            .unwrap();
+        assert_eq!(
+            maze.link(&Point { x: 0, y: 0 }, &Point { x: 1, y: 1 }),
+            Err(MazeError::NotNeighbours(
+                Point { x: 0, y: 0 },
+                Point { x: 1, y: 1 }
+            ))
+        );
+        assert_eq!(
+            maze.link(&Point { x: 2, y: 1 }, &Point { x: 3, y: 1 }),
+            Err(MazeError::OutOfBounds(Point { x: 3, y: 1 }))
+        );
+
+        let walls = maze.walls(&Point { x: 1, y: 0 });
+        assert!(walls.north && !walls.south && walls.east && !walls.west);
+
+        assert_eq!(
+            maze.to_string(),
+            [
+                "+---+---+---+",
+                "| S     |   |",
+                "+---+   +---+",
+                "|   |     E |",
+                "+---+---+---+",
+                "",
+            ]
+            .join("\n")
+        );
+        let path = maze.astar().unwrap().path;
+        assert_eq!(maze.to_text(&path).lines().nth(3), Some("|   | .   E |"));
+
+        maze.unlink(&Point { x: 1, y: 1 }, &Point { x: 1, y: 0 })
+            .unwrap();
+        assert!(maze.astar().is_none());
+    }
+    #[test]
+    fn empty_test() {
+        for (height, width) in [(0, 0), (0, 3), (3, 0)] {
+            let maze = WallMaze::closed(Square { height, width });
+            assert_eq!(maze.to_text(&[]), "");
+            assert_eq!(maze.to_maze(), Err(MazeError::TooSmall { height, width }));
+        }
+
+        // a single room can't hold the start and the destanation apart
+        let single = WallMaze::closed(Square {
+            height: 1,
+            width: 1,
+        });
+        assert_eq!(
+            single.to_maze(),
+            Err(MazeError::TooSmall {
+                height: 1,
+                width: 1
+            })
+        );
+        let mut pair = WallMaze::closed(Square {
+            height: 1,
+            width: 2,
+        });
+        assert!(pair.to_maze().is_ok());
+        pair.destanation = pair.start;
+        assert_eq!(pair.to_maze(), Err(MazeError::Overlaps(pair.start)));
+    }
+}
@@ -0,0 +1,50 @@
+// Compares every stage of a bzip2-like compressor against the stages alone.
+//
+// cargo run --release --example bzip -- [FILE]
+use std::{env, fs, time::Instant};
+
+use codec::{Codec, Pipeline};
+use huffman::Huffman;
+use rle::{binary::Binary, bwt::Bwt, mtf::Mtf, packbits::PackBits};
+
+fn main() {
+    let path = env::args().nth(1).unwrap_or("README.md".into());
+
+    let data = fs::read(&path).unwrap_or_else(|err| panic!("can't read {path}: {err}"));
+
+    println!("{path}: {} bytes\n", data.len());
+
+    let pipelines = [
+        Pipeline::new().stage(Binary),
+        Pipeline::new().stage(PackBits),
+        Pipeline::new().stage(Huffman),
+        Pipeline::new().stage(Binary).stage(Huffman),
+        Pipeline::new().stage(Bwt).stage(Mtf).stage(Huffman),
+        Pipeline::new()
+            .stage(Bwt)
+            .stage(Mtf)
+            .stage(Binary)
+            .stage(Huffman),
+        Pipeline::new()
+            .stage(Bwt)
+            .stage(Mtf)
+            .stage(PackBits)
+            .stage(Huffman),
+    ];
+
+    for pipeline in pipelines.iter() {
+        let now = Instant::now();
+        let report = pipeline.report(&data).unwrap();
+        let encoding = now.elapsed();
+
+        let now = Instant::now();
+        let decoded = pipeline.decode(&report.output).unwrap();
+        let decoding = now.elapsed();
+
+        assert_eq!(decoded, data, "{} doesn't round trip", pipeline.name());
+
+        println!("# {}", pipeline.name());
+        print!("{report}");
+        println!("encode {encoding:?}, decode {decoding:?}\n");
+    }
+}
@@ -0,0 +1,80 @@
+use codec::{Codec, Error};
+
+use crate::runs;
+
+// encodes data as (count, byte) pairs, runs longer than 255 are split
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let mut result = vec![];
+
+    for (byte, mut count) in runs(data) {
+        while count > 0 {
+            let chunk = count.min(u8::MAX as usize);
+            result.push(chunk as u8);
+            result.push(byte);
+            count -= chunk;
+        }
+    }
+
+    result
+}
+
+pub fn decode(data: &[u8]) -> Result<Vec<u8>, Error> {
+    if !data.len().is_multiple_of(2) {
+        return Err(Error::Truncated);
+    }
+
+    let mut result = vec![];
+
+    for pair in data.chunks_exact(2) {
+        let (count, byte) = (pair[0], pair[1]);
+        if count == 0 {
+            return Err(Error::Malformed("run of length zero".into()));
+        }
+        result.extend(std::iter::repeat_n(byte, count as usize));
+    }
+
+    Ok(result)
+}
+
+pub struct Binary;
+
+impl Codec for Binary {
+    fn name(&self) -> String {
+        "rle-binary".into()
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(encode(data))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        decode(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codec::Error;
+
+    use crate::binary::{decode, encode};
+
+    #[test]
+    fn round_trip_test() {
+        let message = b"wwwwddr";
+        let encoded = encode(message);
+        assert_eq!(encoded, [4, b'w', 2, b'd', 1, b'r']);
+        assert_eq!(decode(&encoded).unwrap(), message);
+    }
+    #[test]
+    fn long_run_test() {
+        let message = vec![7; 600];
+        let encoded = encode(&message);
+        assert_eq!(encoded, [255, 7, 255, 7, 90, 7]);
+        assert_eq!(decode(&encoded).unwrap(), message);
+    }
+    #[test]
+    fn malformed_test() {
+        assert_eq!(decode(&[3]), Err(Error::Truncated));
+        assert!(matches!(decode(&[0, b'a']), Err(Error::Malformed(_))));
+    }
+}
@@ -0,0 +1,338 @@
+// Bit-level RLE: a bit stream is stored as its first bit followed by the lengths
+// of the alternating runs, e.g. 0001111101 -> 0, [3, 5, 1, 1].
+// Handy for sparse bitsets and boolean grids, the grid width is up to the caller.
+
+use codec::{Codec, Error};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lengths {
+    // one byte for the first bit, then LEB128 run lengths
+    Varint,
+    // one bit for the first bit, then Elias-gamma run lengths, zero padded to a byte
+    EliasGamma,
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    length: usize,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: vec![],
+            length: 0,
+        }
+    }
+
+    fn push(&mut self, bit: bool) {
+        if self.length.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 0x80 >> (self.length % 8);
+        }
+        self.length += 1;
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn next(&mut self) -> Option<bool> {
+        let byte = self.bytes.get(self.position / 8)?;
+        let bit = byte & (0x80 >> (self.position % 8)) != 0;
+        self.position += 1;
+        Some(bit)
+    }
+}
+
+// splits a bit stream into alternating run lengths
+pub fn runs(bits: &[bool]) -> Vec<usize> {
+    let mut result = vec![];
+    let mut i = 0;
+
+    while i < bits.len() {
+        let mut count = 1;
+        while i + count < bits.len() && bits[i + count] == bits[i] {
+            count += 1;
+        }
+        result.push(count);
+        i += count;
+    }
+
+    result
+}
+
+pub fn encode(bits: &[bool], lengths: Lengths) -> Vec<u8> {
+    let Some(&first) = bits.first() else {
+        return vec![];
+    };
+
+    match lengths {
+        Lengths::Varint => {
+            let mut result = vec![first as u8];
+            for mut run in runs(bits) {
+                while run >= 0x80 {
+                    result.push((run & 0x7F) as u8 | 0x80);
+                    run >>= 7;
+                }
+                result.push(run as u8);
+            }
+            result
+        }
+        Lengths::EliasGamma => {
+            let mut writer = BitWriter::new();
+            writer.push(first);
+            for run in runs(bits) {
+                let width = usize::BITS - run.leading_zeros();
+                for _ in 1..width {
+                    writer.push(false);
+                }
+                for i in (0..width).rev() {
+                    writer.push(run >> i & 1 == 1);
+                }
+            }
+            writer.bytes
+        }
+    }
+}
+
+pub fn decode(data: &[u8], lengths: Lengths) -> Result<Vec<bool>, Error> {
+    let mut result = vec![];
+
+    match lengths {
+        Lengths::Varint => {
+            let (&first, mut rest) = match data.split_first() {
+                Some(split) => split,
+                None => return Ok(result),
+            };
+            if first > 1 {
+                return Err(Error::Malformed(format!("first bit is {first}")));
+            }
+            let mut bit = first == 1;
+            while !rest.is_empty() {
+                let mut run: usize = 0;
+                let mut shift = 0;
+                loop {
+                    let (&byte, tail) = rest.split_first().ok_or(Error::Truncated)?;
+                    rest = tail;
+                    // the 10th byte only has room for its lowest bit
+                    let bits = (byte & 0x7F) as usize;
+                    run |= bits
+                        .checked_shl(shift)
+                        .filter(|shifted| shifted >> shift == bits)
+                        .ok_or_else(|| Error::Malformed("run length overflows".into()))?;
+                    shift += 7;
+                    if byte & 0x80 == 0 {
+                        break;
+                    }
+                }
+                if run == 0 {
+                    return Err(Error::Malformed("run of length zero".into()));
+                }
+                push_run(&mut result, bit, run)?;
+                bit = !bit;
+            }
+        }
+        Lengths::EliasGamma => {
+            let mut reader = BitReader::new(data);
+            let Some(mut bit) = reader.next() else {
+                return Ok(result);
+            };
+            loop {
+                let mut zeros = 0;
+                // running out of bits while counting zeros is the padding at the end
+                loop {
+                    match reader.next() {
+                        Some(true) => break,
+                        Some(false) => zeros += 1,
+                        None if zeros < 8 => return Ok(result),
+                        None => return Err(Error::Truncated),
+                    }
+                }
+                if zeros >= usize::BITS {
+                    return Err(Error::Malformed("run length overflows".into()));
+                }
+                let mut run: usize = 1;
+                for _ in 0..zeros {
+                    run = run << 1 | reader.next().ok_or(Error::Truncated)? as usize;
+                }
+                push_run(&mut result, bit, run)?;
+                bit = !bit;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+// a corrupted length can ask for more memory than there is, which mustn't abort
+fn push_run(result: &mut Vec<bool>, bit: bool, run: usize) -> Result<(), Error> {
+    result
+        .try_reserve(run)
+        .map_err(|_| Error::Malformed(format!("run of {run} bits is too long")))?;
+    result.extend(std::iter::repeat_n(bit, run));
+    Ok(())
+}
+
+// bytes as a most-significant-bit-first bit slice
+pub fn to_bits(data: &[u8]) -> Vec<bool> {
+    data.iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| byte >> i & 1 == 1))
+        .collect()
+}
+
+pub fn from_bits(bits: &[bool]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    for &bit in bits {
+        writer.push(bit);
+    }
+    writer.bytes
+}
+
+pub fn encode_bytes(data: &[u8], lengths: Lengths) -> Vec<u8> {
+    encode(&to_bits(data), lengths)
+}
+
+pub fn decode_bytes(data: &[u8], lengths: Lengths) -> Result<Vec<u8>, Error> {
+    let bits = decode(data, lengths)?;
+    if !bits.len().is_multiple_of(8) {
+        return Err(Error::Malformed(format!(
+            "{} bits don't make whole bytes",
+            bits.len()
+        )));
+    }
+    Ok(from_bits(&bits))
+}
+
+// bit-level RLE over the bits of a byte slice
+pub struct Bits(pub Lengths);
+
+impl Codec for Bits {
+    fn name(&self) -> String {
+        match self.0 {
+            Lengths::Varint => "rle-bits-varint".into(),
+            Lengths::EliasGamma => "rle-bits-gamma".into(),
+        }
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(encode_bytes(data, self.0))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        decode_bytes(data, self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codec::Error;
+
+    use crate::bits::{decode, decode_bytes, encode, encode_bytes, from_bits, runs, Lengths};
+
+    fn parse(s: &str) -> Vec<bool> {
+        s.chars().map(|c| c == '1').collect()
+    }
+
+    #[test]
+    fn runs_test() {
+        assert_eq!(runs(&parse("0001111101")), vec![3, 5, 1, 1]);
+        assert!(runs(&[]).is_empty());
+    }
+    #[test]
+    fn varint_test() {
+        let bits = parse("0001111101");
+        let encoded = encode(&bits, Lengths::Varint);
+        assert_eq!(encoded, [0, 3, 5, 1, 1]);
+        assert_eq!(decode(&encoded, Lengths::Varint).unwrap(), bits);
+
+        let long = vec![true; 300];
+        let encoded = encode(&long, Lengths::Varint);
+        assert_eq!(encoded, [1, 0xAC, 0x02]);
+        assert_eq!(decode(&encoded, Lengths::Varint).unwrap(), long);
+    }
+    #[test]
+    fn elias_gamma_test() {
+        // first bit 0, then gamma(3) = 011, gamma(5) = 00101, gamma(1) = 1, gamma(1) = 1
+        let bits = parse("0001111101");
+        let encoded = encode(&bits, Lengths::EliasGamma);
+        assert_eq!(encoded, [0b0011_0010, 0b1110_0000]);
+        assert_eq!(decode(&encoded, Lengths::EliasGamma).unwrap(), bits);
+    }
+    #[test]
+    fn sparse_grid_test() {
+        // 64x64 grid with a single glider
+        let mut grid = vec![false; 64 * 64];
+        for (row, col) in [(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)] {
+            grid[row * 64 + col] = true;
+        }
+        for lengths in [Lengths::Varint, Lengths::EliasGamma] {
+            let encoded = encode(&grid, lengths);
+            assert!(encoded.len() < 16);
+            assert_eq!(decode(&encoded, lengths).unwrap(), grid);
+        }
+    }
+    #[test]
+    fn bytes_test() {
+        let data = b"\x00\x00\x00\xFF\xFF\x0F\x00";
+        for lengths in [Lengths::Varint, Lengths::EliasGamma] {
+            let encoded = encode_bytes(data, lengths);
+            assert_eq!(decode_bytes(&encoded, lengths).unwrap(), data);
+        }
+        assert!(encode_bytes(&[], Lengths::EliasGamma).is_empty());
+    }
+    #[test]
+    fn malformed_test() {
+        assert!(matches!(
+            decode(&[2, 1], Lengths::Varint),
+            Err(Error::Malformed(_))
+        ));
+        assert!(matches!(
+            decode(&[0, 0], Lengths::Varint),
+            Err(Error::Malformed(_))
+        ));
+        assert_eq!(decode(&[0, 0x80], Lengths::Varint), Err(Error::Truncated));
+        // 63 bits and two more, the second of them doesn't fit in a 64 bit usize
+        let mut long = vec![0];
+        long.extend([0x80; 9]);
+        long.push(0x03);
+        assert!(matches!(
+            decode(&long, Lengths::Varint),
+            Err(Error::Malformed(_))
+        ));
+        long.insert(1, 0x80);
+        assert!(matches!(
+            decode(&long, Lengths::Varint),
+            Err(Error::Malformed(_))
+        ));
+
+        // runs of 2^63 - 1 bits that fit in a usize but not in memory
+        let mut huge = vec![0];
+        huge.extend([0xFF; 8]);
+        huge.push(0x7F);
+        assert!(matches!(
+            decode(&huge, Lengths::Varint),
+            Err(Error::Malformed(_))
+        ));
+        let mut gamma = vec![false; 63];
+        gamma.extend([true; 63]);
+        assert!(matches!(
+            decode(&from_bits(&gamma), Lengths::EliasGamma),
+            Err(Error::Malformed(_))
+        ));
+        // a gamma prefix announcing six more bits that never come
+        assert_eq!(
+            decode(&[0b0000_0001], Lengths::EliasGamma),
+            Err(Error::Truncated)
+        );
+    }
+}
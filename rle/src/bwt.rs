@@ -0,0 +1,148 @@
+// Burrows–Wheeler transform: the last column of the sorted rotations of the input.
+// Equal contexts end up next to each other, so ordinary text turns into long runs
+// once the output goes through move-to-front (see mtf.rs).
+use codec::{Codec, Error};
+
+// returns the last column and the row of the sorted rotations holding the input
+pub fn transform(data: &[u8]) -> (Vec<u8>, usize) {
+    let n = data.len();
+
+    if n == 0 {
+        return (vec![], 0);
+    }
+
+    // prefix doubling: sort rotations by their first k bytes, then 2k, ...
+    let mut rotations: Vec<usize> = (0..n).collect();
+    let mut rank: Vec<usize> = data.iter().map(|&byte| byte as usize).collect();
+    let mut next = vec![0; n];
+    let mut k = 1;
+
+    loop {
+        let key = |i: usize| (rank[i], rank[(i + k) % n]);
+
+        rotations.sort_by_key(|&i| key(i));
+
+        next[rotations[0]] = 0;
+        for w in 1..n {
+            let (prev, current) = (rotations[w - 1], rotations[w]);
+            next[current] = next[prev] + (key(prev) != key(current)) as usize;
+        }
+        std::mem::swap(&mut rank, &mut next);
+
+        if rank[rotations[n - 1]] == n - 1 || k >= n {
+            break;
+        }
+        k *= 2;
+    }
+
+    let index = rotations.iter().position(|&i| i == 0).unwrap();
+    let last = rotations.iter().map(|&i| data[(i + n - 1) % n]).collect();
+
+    (last, index)
+}
+
+pub fn inverse(last: &[u8], index: usize) -> Option<Vec<u8>> {
+    let n = last.len();
+
+    if n == 0 {
+        return Some(vec![]);
+    }
+    if index >= n {
+        return None;
+    }
+
+    // first row of every byte in the sorted first column
+    let mut first = [0; 256];
+    for &byte in last {
+        first[byte as usize] += 1;
+    }
+    let mut sum = 0;
+    for count in first.iter_mut() {
+        (*count, sum) = (sum, sum + *count);
+    }
+
+    // last-to-first mapping
+    let mut lf = vec![0; n];
+    let mut seen = [0; 256];
+    for (i, &byte) in last.iter().enumerate() {
+        lf[i] = first[byte as usize] + seen[byte as usize];
+        seen[byte as usize] += 1;
+    }
+
+    let mut result = vec![0; n];
+    let mut row = index;
+    for i in (0..n).rev() {
+        result[i] = last[row];
+        row = lf[row];
+    }
+
+    Some(result)
+}
+
+// [row of the input: u64 LE] [last column]
+pub struct Bwt;
+
+impl Codec for Bwt {
+    fn name(&self) -> String {
+        "bwt".into()
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let (last, index) = transform(data);
+
+        let mut result = (index as u64).to_le_bytes().to_vec();
+        result.extend_from_slice(&last);
+
+        Ok(result)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let index = data.get(..8).ok_or(Error::Truncated)?;
+        let index = u64::from_le_bytes(index.try_into().unwrap()) as usize;
+
+        inverse(&data[8..], index)
+            .ok_or_else(|| Error::Malformed(format!("row {index} is out of range")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codec::Codec;
+
+    use crate::bwt::{inverse, transform, Bwt};
+
+    #[test]
+    fn banana_test() {
+        let (last, index) = transform(b"banana");
+        assert_eq!(last, b"nnbaaa");
+        assert_eq!(index, 3);
+        assert_eq!(inverse(&last, index).unwrap(), b"banana");
+    }
+    #[test]
+    fn round_trip_test() {
+        let messages: [&[u8]; 5] = [
+            b"",
+            b"a",
+            b"abababab",
+            b"the quick brown fox jumps over the lazy dog",
+            &[0, 255, 0, 255, 1, 1, 1, 0],
+        ];
+        for message in messages {
+            let encoded = Bwt.encode(message).unwrap();
+            assert_eq!(Bwt.decode(&encoded).unwrap(), message);
+        }
+    }
+    #[test]
+    fn malformed_test() {
+        assert!(Bwt.decode(&[1, 2, 3]).is_err());
+        assert!(Bwt.decode(&[9, 0, 0, 0, 0, 0, 0, 0, b'a']).is_err());
+    }
+}
@@ -1,16 +1,24 @@
+use codec::{Codec, Error};
+
+pub mod binary;
+pub mod bits;
+pub mod bwt;
+pub mod mtf;
+pub mod packbits;
+pub mod stats;
+
 pub fn encoding(data: &str) -> String {
     let mut encoded = String::new();
-    let mut i = 0;
+    let mut chars = data.chars().peekable();
 
-    while i < data.len() {
+    while let Some(c) = chars.next() {
         let mut count = 1;
-        while i < data.len() && data.chars().nth(i) == data.chars().nth(i + 1) {
+        while chars.peek() == Some(&c) {
             count += 1;
-            i += 1;
+            chars.next();
         }
         encoded.push_str(&count.to_string());
-        encoded.push(data.chars().nth(i).unwrap());
-        i += 1;
+        encoded.push(c);
     }
 
     encoded
@@ -33,9 +41,73 @@ pub fn decoding(data: &str) -> String {
     decoded
 }
 
+// same as decoding, but returns None on a missing count or a dangling count at the end
+pub fn try_decoding(data: &str) -> Option<String> {
+    let mut decoded = String::new();
+    let mut current_num = String::new();
+
+    for c in data.chars() {
+        if c.is_ascii_digit() {
+            current_num.push(c);
+        } else {
+            let count = current_num.parse::<usize>().ok()?;
+            decoded.push_str(&c.to_string().repeat(count));
+            current_num.clear();
+        }
+    }
+
+    if !current_num.is_empty() {
+        return None;
+    }
+
+    Some(decoded)
+}
+
+// splits data into (byte, run length) pairs
+pub fn runs(data: &[u8]) -> Vec<(u8, usize)> {
+    let mut result: Vec<(u8, usize)> = vec![];
+
+    for &byte in data {
+        match result.last_mut() {
+            Some((value, count)) if *value == byte => *count += 1,
+            _ => result.push((byte, 1)),
+        }
+    }
+
+    result
+}
+
+// the `<count><char>` text format as a codec, input has to be UTF-8 without digits
+pub struct Text;
+
+impl Codec for Text {
+    fn name(&self) -> String {
+        "rle-text".into()
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let text = std::str::from_utf8(data)
+            .map_err(|_| Error::Unsupported("text format needs UTF-8 input".into()))?;
+        if text.chars().any(|c| c.is_numeric()) {
+            return Err(Error::Unsupported("text format can't encode digits".into()));
+        }
+        Ok(encoding(text).into_bytes())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let text = std::str::from_utf8(data)
+            .map_err(|_| Error::Malformed("encoded text isn't UTF-8".into()))?;
+        try_decoding(text)
+            .map(String::into_bytes)
+            .ok_or_else(|| Error::Malformed("expected <count><char> pairs".into()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{decoding, encoding};
+    use codec::{Codec, Error, Pipeline};
+
+    use crate::{binary::Binary, decoding, encoding, runs, try_decoding, Text};
 
     #[test]
     fn encoding_test() {
@@ -53,4 +125,50 @@ mod tests {
         println!("Decoded Message: {result}");
         assert_eq!(result, "wwwwddrr");
     }
+    #[test]
+    fn unicode_test() {
+        let message = "ééén";
+        let result = encoding(message);
+        assert_eq!(result, "3é1n");
+        assert_eq!(decoding(&result), message);
+    }
+    #[test]
+    fn try_decoding_test() {
+        assert_eq!(try_decoding("12a3b").as_deref(), Some("aaaaaaaaaaaabbb"));
+        assert_eq!(try_decoding("a"), None);
+        assert_eq!(try_decoding("3a4"), None);
+    }
+    #[test]
+    fn text_codec_test() {
+        let encoded = Text.encode("wwwwddr".as_bytes()).unwrap();
+        assert_eq!(encoded, b"4w2d1r");
+        assert_eq!(Text.decode(&encoded).unwrap(), b"wwwwddr");
+        assert!(matches!(Text.encode(b"a1"), Err(Error::Unsupported(_))));
+        assert!(matches!(Text.decode(b"4"), Err(Error::Malformed(_))));
+
+        // whitespace is a symbol like any other
+        for message in ["hello\n", "tabs\t\t", "  \n\n"] {
+            let encoded = Text.encode(message.as_bytes()).unwrap();
+            assert_eq!(Text.decode(&encoded).unwrap(), message.as_bytes());
+        }
+        assert_eq!(Text.decode(b"2\n").unwrap(), b"\n\n");
+    }
+    #[test]
+    fn pipeline_test() {
+        let message = "aaaaaaaaaabbbbbbbbbbbbcccccccccccccccccccccccdddddddddddddd".repeat(20);
+        let pipeline = Pipeline::new().stage(Binary).stage(huffman::Huffman);
+
+        let report = pipeline.report(message.as_bytes()).unwrap();
+        println!("{report}");
+        assert!(report.stages[0].output < report.stages[0].input);
+        assert!(report.stages[1].output < report.stages[1].input);
+
+        let decoded = pipeline.decode(&report.output).unwrap();
+        assert_eq!(decoded, message.as_bytes());
+    }
+    #[test]
+    fn runs_test() {
+        assert_eq!(runs(b"aaabcc"), vec![(b'a', 3), (b'b', 1), (b'c', 2)]);
+        assert!(runs(b"").is_empty());
+    }
 }
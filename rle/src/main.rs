@@ -0,0 +1,154 @@
+use std::{
+    env, fs,
+    io::{self, Read, Write},
+    process,
+};
+
+use codec::Codec;
+use rle::{
+    binary::Binary,
+    bits::{Bits, Lengths},
+    packbits::PackBits,
+    stats::Stats,
+    Text,
+};
+
+const USAGE: &str =
+    "Usage: rle <encode|decode> [--format text|binary|packbits|bits] [--stats] [FILE]
+
+Reads FILE (or stdin when FILE is missing or '-') and writes the result to stdout.
+
+Options:
+  -f, --format <FORMAT>  text, binary, packbits or bits [default: binary]
+  -s, --stats            print the run-length histogram and compression ratio to stderr
+  -h, --help             print this message";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Encode,
+    Decode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Binary,
+    PackBits,
+    Bits,
+}
+
+impl Format {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "text" => Ok(Self::Text),
+            "binary" => Ok(Self::Binary),
+            "packbits" => Ok(Self::PackBits),
+            "bits" => Ok(Self::Bits),
+            _ => Err(format!("unknown format '{s}'")),
+        }
+    }
+
+    fn codec(&self) -> Box<dyn Codec> {
+        match self {
+            Self::Text => Box::new(Text),
+            Self::Binary => Box::new(Binary),
+            Self::PackBits => Box::new(PackBits),
+            Self::Bits => Box::new(Bits(Lengths::EliasGamma)),
+        }
+    }
+}
+
+struct Args {
+    mode: Mode,
+    format: Format,
+    stats: bool,
+    path: Option<String>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut mode = None;
+        let mut format = Format::Binary;
+        let mut stats = false;
+        let mut path = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    process::exit(0);
+                }
+                "-s" | "--stats" => stats = true,
+                "-f" | "--format" => {
+                    let value = args.next().ok_or("--format needs a value")?;
+                    format = Format::parse(&value)?;
+                }
+                "encode" if mode.is_none() => mode = Some(Mode::Encode),
+                "decode" if mode.is_none() => mode = Some(Mode::Decode),
+                _ if arg.starts_with("--format=") => {
+                    format = Format::parse(&arg["--format=".len()..])?;
+                }
+                _ if arg.starts_with('-') && arg != "-" => {
+                    return Err(format!("unknown option '{arg}'"));
+                }
+                _ if mode.is_some() && path.is_none() => path = Some(arg),
+                _ => return Err(format!("unexpected argument '{arg}'")),
+            }
+        }
+
+        Ok(Self {
+            mode: mode.ok_or("missing subcommand, expected 'encode' or 'decode'")?,
+            format,
+            stats,
+            path,
+        })
+    }
+}
+
+fn read_input(path: &Option<String>) -> io::Result<Vec<u8>> {
+    match path.as_deref() {
+        None | Some("-") => {
+            let mut data = vec![];
+            io::stdin().read_to_end(&mut data)?;
+            Ok(data)
+        }
+        Some(path) => fs::read(path),
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let input = read_input(&args.path).map_err(|e| e.to_string())?;
+
+    let codec = args.format.codec();
+
+    let output = match args.mode {
+        Mode::Encode => codec.encode(&input),
+        Mode::Decode => codec.decode(&input),
+    }
+    .map_err(|e| e.to_string())?;
+
+    io::stdout().write_all(&output).map_err(|e| e.to_string())?;
+
+    if args.stats {
+        // histogram is always taken over the uncompressed side
+        let stats = match args.mode {
+            Mode::Encode => Stats::new(&input, output.len()),
+            Mode::Decode => Stats::new(&output, input.len()),
+        };
+        eprint!("{stats}");
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("rle: {err}\n\n{USAGE}");
+        process::exit(2);
+    });
+
+    if let Err(err) = run(args) {
+        eprintln!("rle: {err}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,67 @@
+// Move-to-front: every byte becomes its position in a list of recently used bytes,
+// so the runs of similar bytes coming out of the BWT turn into runs of small numbers.
+use codec::{Codec, Error};
+
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let mut order: Vec<u8> = (0..=255).collect();
+
+    data.iter()
+        .map(|byte| {
+            let index = order.iter().position(|x| x == byte).unwrap();
+            order.remove(index);
+            order.insert(0, *byte);
+            index as u8
+        })
+        .collect()
+}
+
+pub fn decode(data: &[u8]) -> Vec<u8> {
+    let mut order: Vec<u8> = (0..=255).collect();
+
+    data.iter()
+        .map(|&index| {
+            let byte = order.remove(index as usize);
+            order.insert(0, byte);
+            byte
+        })
+        .collect()
+}
+
+pub struct Mtf;
+
+impl Codec for Mtf {
+    fn name(&self) -> String {
+        "mtf".into()
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(encode(data))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(decode(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bwt,
+        mtf::{decode, encode},
+        runs,
+    };
+
+    #[test]
+    fn mtf_test() {
+        let encoded = encode(b"bananaaa");
+        assert_eq!(encoded, [98, 98, 110, 1, 1, 1, 0, 0]);
+        assert_eq!(decode(&encoded), b"bananaaa");
+    }
+    #[test]
+    fn bwt_mtf_runs_test() {
+        let text = "the rain in spain stays mainly in the plain. ".repeat(8);
+        let (last, _) = bwt::transform(text.as_bytes());
+        let transformed = encode(&last);
+        assert!(runs(&transformed).len() < runs(text.as_bytes()).len() / 3);
+    }
+}
@@ -0,0 +1,122 @@
+use codec::{Codec, Error};
+
+// PackBits as used in TIFF and MacPaint.
+// header n in 0..=127: copy the next n + 1 bytes literally
+// header n in -127..=-1: repeat the next byte 1 - n times
+// header -128: no-op
+const MAX_RUN: usize = 128;
+
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    fn flush(result: &mut Vec<u8>, literals: &mut Vec<u8>) {
+        if !literals.is_empty() {
+            result.push((literals.len() - 1) as u8);
+            result.append(literals);
+        }
+    }
+
+    let mut result = vec![];
+    let mut literals = vec![];
+    let mut i = 0;
+
+    while i < data.len() {
+        let mut run = 1;
+        while i + run < data.len() && data[i + run] == data[i] && run < MAX_RUN {
+            run += 1;
+        }
+
+        // a run of two only pays off when it doesn't interrupt a literal block
+        if run >= 3 || (run == 2 && literals.is_empty()) {
+            flush(&mut result, &mut literals);
+            result.push((1 - run as i16) as i8 as u8);
+            result.push(data[i]);
+            i += run;
+        } else {
+            literals.push(data[i]);
+            i += 1;
+            if literals.len() == MAX_RUN {
+                flush(&mut result, &mut literals);
+            }
+        }
+    }
+
+    flush(&mut result, &mut literals);
+
+    result
+}
+
+pub fn decode(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut result = vec![];
+    let mut i = 0;
+
+    while i < data.len() {
+        let header = data[i] as i8;
+        i += 1;
+
+        match header {
+            -128 => {}
+            0..=127 => {
+                let count = header as usize + 1;
+                result.extend_from_slice(data.get(i..i + count).ok_or(Error::Truncated)?);
+                i += count;
+            }
+            _ => {
+                let count = (1 - header as i16) as usize;
+                let byte = *data.get(i).ok_or(Error::Truncated)?;
+                result.extend(std::iter::repeat_n(byte, count));
+                i += 1;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+pub struct PackBits;
+
+impl Codec for PackBits {
+    fn name(&self) -> String {
+        "packbits".into()
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(encode(data))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        decode(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codec::Error;
+
+    use crate::packbits::{decode, encode};
+
+    #[test]
+    fn apple_example_test() {
+        // example from Apple's technical note TN1023
+        let encoded = [
+            0xFE, 0xAA, 0x02, 0x80, 0x00, 0x2A, 0xFD, 0xAA, 0x03, 0x80, 0x00, 0x2A, 0x22, 0xF7,
+            0xAA,
+        ];
+        let decoded = decode(&encoded).unwrap();
+        assert_eq!(decoded.len(), 24);
+        assert_eq!(decode(&encode(&decoded)).unwrap(), decoded);
+    }
+    #[test]
+    fn round_trip_test() {
+        let mut message = vec![];
+        message.extend_from_slice(b"abcdefg");
+        message.extend(std::iter::repeat_n(b'x', 300));
+        message.extend((0..=255).cycle().take(400));
+        let encoded = encode(&message);
+        assert!(encoded.len() < message.len());
+        assert_eq!(decode(&encoded).unwrap(), message);
+    }
+    #[test]
+    fn truncated_test() {
+        assert_eq!(decode(&[0x02, b'a']), Err(Error::Truncated));
+        assert_eq!(decode(&[0xFD]), Err(Error::Truncated));
+    }
+}
@@ -0,0 +1,67 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::runs;
+
+#[derive(Debug)]
+pub struct Stats {
+    pub original: usize,
+    pub encoded: usize,
+    // run length -> number of runs of that length
+    pub histogram: BTreeMap<usize, usize>,
+}
+
+impl Stats {
+    pub fn new(original: &[u8], encoded: usize) -> Self {
+        let mut histogram = BTreeMap::new();
+
+        for (_, length) in runs(original) {
+            *histogram.entry(length).or_insert(0) += 1;
+        }
+
+        Self {
+            original: original.len(),
+            encoded,
+            histogram,
+        }
+    }
+
+    pub fn runs(&self) -> usize {
+        self.histogram.values().sum()
+    }
+
+    pub fn ratio(&self) -> f64 {
+        if self.original == 0 {
+            return 1.0;
+        }
+        self.encoded as f64 / self.original as f64
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "original: {} bytes", self.original)?;
+        writeln!(f, "encoded:  {} bytes", self.encoded)?;
+        writeln!(f, "ratio:    {:.2}%", self.ratio() * 100.0)?;
+        writeln!(f, "runs:     {}", self.runs())?;
+        writeln!(f, "length\tcount\n- - - - - - - -")?;
+        for (length, count) in self.histogram.iter() {
+            writeln!(f, "{length}\t{count}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stats::Stats;
+
+    #[test]
+    fn histogram_test() {
+        let stats = Stats::new(b"aaabccddd", 6);
+        assert_eq!(stats.runs(), 4);
+        assert_eq!(stats.histogram.get(&3), Some(&2));
+        assert_eq!(stats.histogram.get(&2), Some(&1));
+        assert_eq!(stats.histogram.get(&1), Some(&1));
+        assert!((stats.ratio() - 6.0 / 9.0).abs() < f64::EPSILON);
+    }
+}